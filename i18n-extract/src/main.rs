@@ -0,0 +1,131 @@
+//! Extracts the keys that a Dioxus app translates into its JSON languages.
+//!
+//! ```text
+//! dioxus-i18n-extract --locales <dir> [--check] [<source dir>...]
+//! ```
+//!
+//! The source directories (the current directory by default) are scanned for `translate!` and `translate_rsx!`
+//! invocations and `UseI18::translate` calls. Every `.json` language in the locales directory then gets a placeholder
+//! text for each new key, and the texts of keys that aren't translated anymore are moved to its `stale` texts.
+//!
+//! With `--check` the languages aren't changed, and the exit code is 1 if any of them would be.
+
+mod merge;
+mod scan;
+
+use serde::Serialize;
+use serde_json::{ser::PrettyFormatter, Serializer, Value};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{env, fs};
+
+const USAGE: &str = "Usage: dioxus-i18n-extract --locales <dir> [--check] [<source dir>...]";
+
+struct Args {
+    locales: PathBuf,
+    sources: Vec<PathBuf>,
+    check: bool,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut locales = None;
+        let mut sources = Vec::new();
+        let mut check = false;
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--locales" => locales = Some(args.next().ok_or("`--locales` needs a directory")?),
+                "--check" => check = true,
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+                _ => sources.push(PathBuf::from(arg)),
+            }
+        }
+        if sources.is_empty() {
+            sources.push(PathBuf::from("."));
+        }
+        Ok(Self {
+            locales: PathBuf::from(locales.ok_or("`--locales` is required")?),
+            sources,
+            check,
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(&args) {
+        Ok(true) if args.check => ExitCode::FAILURE,
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Extracts the keys into the languages, returning whether any of them changed.
+fn run(args: &Args) -> Result<bool, String> {
+    let mut keys = scan::Keys::new();
+    for source in &args.sources {
+        scan::scan_dir(source, &mut keys)
+            .map_err(|err| format!("failed to scan {}: {}", source.display(), err))?;
+    }
+    println!("Found {} keys", keys.len());
+
+    let mut languages = fs::read_dir(&args.locales)
+        .map_err(|err| format!("failed to read {}: {}", args.locales.display(), err))?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect::<Vec<_>>();
+    languages.sort();
+
+    let mut changed = false;
+    for path in languages {
+        let changes = extract_into(&path, &keys, args.check)
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        if !changes.is_empty() {
+            changed = true;
+            println!(
+                "{}: {} added, {} restored, {} stale",
+                path.display(),
+                changes.added.len(),
+                changes.restored.len(),
+                changes.stale.len()
+            );
+        }
+    }
+    Ok(changed)
+}
+
+fn extract_into(path: &Path, keys: &scan::Keys, check: bool) -> Result<merge::Changes, String> {
+    let source = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let mut language: Value = serde_json::from_str(&source).map_err(|err| err.to_string())?;
+    let changes = merge::merge(&mut language, keys)?;
+    if check || changes.is_empty() {
+        return Ok(changes);
+    }
+
+    // Keep the indentation of the languages in this repository
+    let mut json = Vec::new();
+    let mut serializer =
+        Serializer::with_formatter(&mut json, PrettyFormatter::with_indent(b"    "));
+    language
+        .serialize(&mut serializer)
+        .map_err(|err| err.to_string())?;
+    if source.ends_with('\n') {
+        json.push(b'\n');
+    }
+    fs::write(path, json).map_err(|err| err.to_string())?;
+    Ok(changes)
+}
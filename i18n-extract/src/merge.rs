@@ -0,0 +1,192 @@
+//! Merging the extracted keys into JSON languages.
+
+use serde_json::{Map, Value};
+
+use crate::scan::Keys;
+
+/// The object of a language that keeps the texts of keys that are no longer translated.
+///
+/// It's ignored when the language is parsed, and a text moves back to `texts` when its key is translated again.
+pub const STALE: &str = "stale";
+
+/// The plural categories a plural text can have a variant for.
+const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// The keys that were changed in a language.
+#[derive(Debug, Default, PartialEq)]
+pub struct Changes {
+    /// Keys that were added with a placeholder text.
+    pub added: Vec<String>,
+    /// Keys that were added with their stale text.
+    pub restored: Vec<String>,
+    /// Keys that were moved to the stale texts.
+    pub stale: Vec<String>,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.restored.is_empty() && self.stale.is_empty()
+    }
+}
+
+/// Adds the keys a language doesn't have yet and moves the texts of the keys that aren't translated anymore to [`STALE`].
+///
+/// New keys get a placeholder text with the key and its parameters, e.g. `⚠ messages.hello {name}`.
+pub fn merge(language: &mut Value, keys: &Keys) -> Result<Changes, String> {
+    let language = language
+        .as_object_mut()
+        .ok_or("the language isn't an object")?;
+    let mut stale = match language.shift_remove(STALE) {
+        Some(Value::Object(stale)) => stale,
+        Some(_) => return Err(format!("`{STALE}` isn't an object")),
+        None => Map::new(),
+    };
+    let texts = language
+        .entry("texts")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or("`texts` isn't an object")?;
+
+    let mut changes = Changes::default();
+    let mut existing = Vec::new();
+    texts_paths(texts, &[], &mut existing);
+    for (key, location) in &existing {
+        if !keys.contains_key(key) {
+            if let Some(text) = remove(texts, location) {
+                stale.insert(key.clone(), text);
+                changes.stale.push(key.clone());
+            }
+        }
+    }
+
+    for (key, params) in keys {
+        if existing.iter().any(|(existing, _)| existing == key) {
+            continue;
+        }
+        let text = match stale.remove(key) {
+            Some(text) => {
+                changes.restored.push(key.clone());
+                text
+            }
+            None => {
+                changes.added.push(key.clone());
+                let params = params.iter().map(|param| format!(" {{{param}}}"));
+                Value::String(format!("⚠ {key}{}", params.collect::<String>()))
+            }
+        };
+        insert(texts, key, text);
+    }
+
+    if !stale.is_empty() {
+        language.insert(STALE.to_string(), Value::Object(stale));
+    }
+    Ok(changes)
+}
+
+/// Whether an object is a plural text rather than a group of texts.
+fn is_plural(texts: &Map<String, Value>) -> bool {
+    texts.contains_key("other")
+        && texts
+            .iter()
+            .all(|(key, value)| PLURAL_CATEGORIES.contains(&key.as_str()) && value.is_string())
+}
+
+/// Collects the key of every text, with the names of the objects it's in.
+///
+/// Like when a key is translated, `"messages": { "hello": ... }` and `"messages.hello": ...` both have the key `messages.hello`.
+fn texts_paths(
+    texts: &Map<String, Value>,
+    location: &[String],
+    paths: &mut Vec<(String, Vec<String>)>,
+) {
+    for (name, text) in texts {
+        let mut location = location.to_vec();
+        location.push(name.clone());
+        match text {
+            Value::Object(group) if !is_plural(group) => texts_paths(group, &location, paths),
+            _ => paths.push((location.join("."), location)),
+        }
+    }
+}
+
+/// Removes a text, along with the groups that are empty without it.
+fn remove(texts: &mut Map<String, Value>, location: &[String]) -> Option<Value> {
+    let (name, rest) = location.split_first()?;
+    if rest.is_empty() {
+        return texts.shift_remove(name);
+    }
+    let group = texts.get_mut(name)?.as_object_mut()?;
+    let text = remove(group, rest);
+    if group.is_empty() {
+        texts.shift_remove(name);
+    }
+    text
+}
+
+/// Inserts a text, creating the groups of its key.
+fn insert(texts: &mut Map<String, Value>, key: &str, text: Value) {
+    if let Some((name, rest)) = key.split_once('.') {
+        let group = texts
+            .entry(name)
+            .or_insert_with(|| Value::Object(Map::new()));
+        match group {
+            Value::Object(group) if !is_plural(group) => return insert(group, rest, text),
+            // The name is a text itself, so the key is kept whole
+            _ => {}
+        }
+    }
+    texts.insert(key.to_string(), text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn merges_keys() {
+        let mut language: Value = serde_json::from_str(
+            r#"{
+                "id": "en-US",
+                "texts": {
+                    "messages": { "hello_world": "Hello World!", "old": "Old" },
+                    "messages.hello": "Hello {name}",
+                    "files": { "one": "{count} file", "other": "{count} files" }
+                },
+                "stale": { "bye": "Bye" }
+            }"#,
+        )
+        .unwrap();
+        let keys = Keys::from([
+            ("messages.hello_world".to_string(), BTreeSet::new()),
+            (
+                "messages.hello".to_string(),
+                BTreeSet::from(["name".to_string()]),
+            ),
+            ("files".to_string(), BTreeSet::from(["count".to_string()])),
+            ("bye".to_string(), BTreeSet::new()),
+            (
+                "settings.title".to_string(),
+                BTreeSet::from(["user".to_string()]),
+            ),
+        ]);
+
+        let changes = merge(&mut language, &keys).unwrap();
+        assert_eq!(
+            changes,
+            Changes {
+                added: vec!["settings.title".to_string()],
+                restored: vec!["bye".to_string()],
+                stale: vec!["messages.old".to_string()],
+            }
+        );
+        assert_eq!(
+            language["texts"]["settings"]["title"],
+            "⚠ settings.title {user}"
+        );
+        assert_eq!(language["texts"]["bye"], "Bye");
+        assert_eq!(language["stale"]["messages.old"], "Old");
+
+        assert!(merge(&mut language, &keys).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Finding the keys that are translated in Rust sources.
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::str::FromStr;
+use std::{fs, io};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, ExprLit, Ident, Lit, LitStr, Token,
+};
+
+/// The translated keys and the parameters each of them is translated with.
+pub type Keys = BTreeMap<String, BTreeSet<String>>;
+
+/// The macros that translate a key, e.g. `translate!(i18, "key", name: value)`.
+const MACROS: [&str; 2] = ["translate", "translate_rsx"];
+
+/// The methods of `UseI18` that translate a key, e.g. `i18.translate("key")`.
+const METHODS: [&str; 3] = ["translate", "translate_with_params", "translate_rsx"];
+
+/// Collects the keys translated in the Rust files of a directory and its subdirectories.
+pub fn scan_dir(dir: &Path, keys: &mut Keys) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if path.is_dir() {
+            // Skip build output and hidden directories such as `.git`
+            if name != "target" && !name.starts_with('.') {
+                scan_dir(&path, keys)?;
+            }
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            let source = fs::read_to_string(&path)?;
+            match TokenStream::from_str(&source) {
+                Ok(tokens) => scan_tokens(&path, tokens, keys),
+                Err(err) => eprintln!("warning: skipping {}: {}", path.display(), err),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Collects the keys translated in a token stream, including the ones in macros such as `rsx!`.
+pub fn scan_tokens(path: &Path, tokens: TokenStream, keys: &mut Keys) {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    for (i, token) in tokens.iter().enumerate() {
+        let ident = match token {
+            TokenTree::Group(group) => {
+                scan_tokens(path, group.stream(), keys);
+                continue;
+            }
+            TokenTree::Ident(ident) => ident,
+            _ => continue,
+        };
+        let name = ident.to_string();
+        let previous = i.checked_sub(1).and_then(|i| tokens.get(i));
+
+        match (previous, tokens.get(i + 1), tokens.get(i + 2)) {
+            (_, Some(TokenTree::Punct(bang)), Some(TokenTree::Group(args)))
+                if bang.as_char() == '!' && MACROS.contains(&name.as_str()) =>
+            {
+                match syn::parse2::<MacroCall>(args.stream()) {
+                    Ok(call) => add_key(keys, call.key.value(), call.params),
+                    Err(_) => {
+                        let line = ident.span().start().line;
+                        eprintln!(
+                            "warning: {}:{}: the key of `{}!` isn't a string literal",
+                            path.display(),
+                            line,
+                            name
+                        );
+                    }
+                }
+            }
+            (Some(TokenTree::Punct(dot)), Some(TokenTree::Group(args)), _)
+                if dot.as_char() == '.'
+                    && args.delimiter() == Delimiter::Parenthesis
+                    && METHODS.contains(&name.as_str()) =>
+            {
+                // Other types can have methods with the same names, so calls without a literal key are ignored
+                if let Ok(call) = syn::parse2::<MethodCall>(args.stream()) {
+                    add_key(keys, call.key, call.params);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn add_key(keys: &mut Keys, key: String, params: impl IntoIterator<Item = String>) {
+    keys.entry(key).or_default().extend(params);
+}
+
+/// The arguments of `translate!(i18, "key", name: value, ...)` or `translate_rsx!(i18, "key", name: value; tag: render)`.
+struct MacroCall {
+    key: LitStr,
+    params: Vec<String>,
+}
+
+impl Parse for MacroCall {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Expr>()?;
+        input.parse::<Token![,]>()?;
+        let key = input.parse()?;
+        let mut params = Vec::new();
+        while input.peek(Token![,]) && input.peek2(Ident) && input.peek3(Token![:]) {
+            input.parse::<Token![,]>()?;
+            params.push(input.parse::<Ident>()?.to_string());
+            input.parse::<Token![:]>()?;
+            input.parse::<Expr>()?;
+        }
+        // The tags of `translate_rsx!`
+        input.parse::<TokenStream>()?;
+        Ok(Self { key, params })
+    }
+}
+
+/// The arguments of `i18.translate("key")` or `i18.translate_with_params("key", params)`.
+///
+/// The parameters are found when they are created from a literal, e.g. `HashMap::from([("name", value)])`.
+struct MethodCall {
+    key: String,
+    params: Vec<String>,
+}
+
+impl Parse for MethodCall {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+        let mut args = args.iter();
+        let Some(Expr::Lit(ExprLit {
+            lit: Lit::Str(key), ..
+        })) = args.next()
+        else {
+            return Err(input.error("the key isn't a string literal"));
+        };
+        let mut params = Vec::new();
+        if let Some(arg) = args.next() {
+            literal_params(arg, &mut params);
+        }
+        Ok(Self {
+            key: key.value(),
+            params,
+        })
+    }
+}
+
+/// Collects the names of `[("name", value), ...]` parameters, also when they are passed to a function like `HashMap::from`.
+fn literal_params(expr: &Expr, params: &mut Vec<String>) {
+    match expr {
+        Expr::Array(array) => {
+            for elem in &array.elems {
+                if let Expr::Tuple(tuple) = elem {
+                    if let Some(Expr::Lit(ExprLit {
+                        lit: Lit::Str(name),
+                        ..
+                    })) = tuple.elems.first()
+                    {
+                        params.push(name.value());
+                    }
+                }
+            }
+        }
+        Expr::Call(call) => {
+            for arg in &call.args {
+                literal_params(arg, params);
+            }
+        }
+        Expr::MethodCall(call) => literal_params(&call.receiver, params),
+        Expr::Reference(reference) => literal_params(&reference.expr, params),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_translated_keys() {
+        let source = r#"
+            fn Body() -> Element {
+                let i18 = use_i18();
+                let title = i18.translate("title");
+                let count = i18.translate_with_params("files", HashMap::from([("count", 2.to_string())]));
+                let moved = position.translate(1.0, 2.0);
+                rsx! {
+                    p { {translate!(i18, "messages.hello", name: "Dioxus", other: 1)} }
+                    p { {translate_rsx!(i18, "terms", url: URL; link: |children| rsx! { a { {children} } })} }
+                }
+            }
+        "#;
+        let mut keys = Keys::new();
+        scan_tokens(Path::new("main.rs"), source.parse().unwrap(), &mut keys);
+
+        let params = |params: &[&str]| params.iter().map(|param| param.to_string()).collect();
+        assert_eq!(
+            keys,
+            Keys::from([
+                ("files".to_string(), params(&["count"])),
+                ("messages.hello".to_string(), params(&["name", "other"])),
+                ("terms".to_string(), params(&["url"])),
+                ("title".to_string(), params(&[])),
+            ])
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Procedural macros for `dioxus-sdk`.
+
+use proc_macro::TokenStream;
+
+mod translations;
+
+/// Generates typed accessors for the keys of translation files.
+///
+/// The first file is the reference: a method is generated for every key in it, taking one argument per parameter the text uses.
+/// Unknown keys and missing or extra parameters fail to compile instead of rendering an empty string at runtime.
+/// Any other files are checked against the reference, and fail the build if they contain keys or parameters the reference doesn't.
+///
+/// Paths are relative to the crate's `Cargo.toml`. Both JSON and Fluent (`.ftl`) files are supported.
+/// The macro is re-exported by `dioxus_sdk::i18n` with the `i18n_macro` feature.
+///
+/// ```rust, ignore
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::i18n::{translations, use_i18};
+///
+/// translations!(pub Texts, "src/en-US.json", "src/es-ES.json");
+///
+/// fn Body() -> Element {
+///     let texts = Texts::new(use_i18());
+///     rsx! {
+///         p { {texts.messages_hello_world()} }
+///         p { {texts.messages_hello("Dioxus")} }
+///     }
+/// }
+/// ```
+#[proc_macro]
+pub fn translations(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as translations::TranslationsInput);
+    translations::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
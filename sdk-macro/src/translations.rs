@@ -0,0 +1,427 @@
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, Pattern, PatternElement};
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Ident, LitStr, Token, Visibility,
+};
+
+/// The plural categories a plural text can have a variant for.
+const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// The parameter that selects the variant of a plural text.
+const PLURAL_PARAM: &str = "count";
+
+/// The keys of a translation file and the parameters each of them uses.
+type Keys = BTreeMap<String, BTreeSet<String>>;
+
+pub struct TranslationsInput {
+    vis: Visibility,
+    name: Ident,
+    files: Vec<LitStr>,
+}
+
+impl Parse for TranslationsInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let vis = input.parse()?;
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let files = Punctuated::<LitStr, Token![,]>::parse_separated_nonempty(input)?
+            .into_iter()
+            .collect();
+        Ok(Self { vis, name, files })
+    }
+}
+
+pub fn expand(input: TranslationsInput) -> syn::Result<TokenStream> {
+    let TranslationsInput { vis, name, files } = input;
+
+    let mut translations = files
+        .iter()
+        .map(|file| Ok((file, read_keys(file)?)))
+        .collect::<syn::Result<Vec<_>>>()?
+        .into_iter();
+    let (reference_file, reference) = translations.next().expect("at least one file is parsed");
+
+    // Every other file may only use the keys and parameters of the reference file
+    for (file, keys) in translations {
+        for (key, params) in keys {
+            let Some(reference_params) = reference.get(&key) else {
+                return Err(syn::Error::new(
+                    file.span(),
+                    format!("`{}` is not in {}", key, reference_file.value()),
+                ));
+            };
+            if let Some(param) = params.difference(reference_params).next() {
+                return Err(syn::Error::new(
+                    file.span(),
+                    format!(
+                        "`{}` uses the parameter `{}` which isn't used in {}",
+                        key,
+                        param,
+                        reference_file.value()
+                    ),
+                ));
+            }
+        }
+    }
+
+    let methods = methods(reference_file, &reference)?;
+
+    // Rebuild when the translation files change
+    let includes = files
+        .iter()
+        .map(|file| {
+            let path = file_path(file)?.to_string_lossy().into_owned();
+            Ok(quote! { const _: &str = include_str!(#path); })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        #[derive(Clone, Copy, PartialEq)]
+        #vis struct #name {
+            i18: ::dioxus_sdk::i18n::UseI18,
+        }
+
+        impl #name {
+            pub fn new(i18: ::dioxus_sdk::i18n::UseI18) -> Self {
+                #(#includes)*
+                Self { i18 }
+            }
+
+            #(#methods)*
+        }
+    })
+}
+
+/// Creates a method that translates each key, with an argument for each of its parameters.
+fn methods(file: &LitStr, keys: &Keys) -> syn::Result<Vec<TokenStream>> {
+    let mut methods = BTreeMap::new();
+    for (key, params) in keys {
+        let name = ident_name(key);
+        if let Some(other) = methods.get(&name).map(|(other, _)| other) {
+            return Err(syn::Error::new(
+                file.span(),
+                format!("`{other}` and `{key}` would both be translated by the method `{name}`, rename one of them"),
+            ));
+        }
+        let method = ident(file, key, &name)?;
+
+        let mut args = BTreeMap::new();
+        for param in params {
+            let name = ident_name(param);
+            if let Some(other) = args.get(&name).map(|(other, _)| other) {
+                return Err(syn::Error::new(
+                    file.span(),
+                    format!("the parameters `{other}` and `{param}` of `{key}` would both be the argument `{name}`, rename one of them"),
+                ));
+            }
+            let arg = ident(file, key, &name)?;
+            args.insert(name, (param, arg));
+        }
+        let inserts = args.values().map(|(param, arg)| {
+            quote! { params.insert(#param, ::std::string::ToString::to_string(&#arg)); }
+        });
+        let args = args.values().map(|(_, arg)| arg);
+
+        let doc = format!("Translates `{key}`.");
+        let tokens = quote! {
+            #[doc = #doc]
+            pub fn #method(&self, #(#args: impl ::std::string::ToString),*) -> ::std::string::String {
+                #[allow(unused_mut)]
+                let mut params = ::std::collections::HashMap::new();
+                #(#inserts)*
+                self.i18.translate_with_params(#key, params)
+            }
+        };
+        methods.insert(name, (key, tokens));
+    }
+    Ok(methods.into_values().map(|(_, tokens)| tokens).collect())
+}
+
+/// The name of the method for a key, or of the argument for a parameter.
+fn ident_name(key: &str) -> String {
+    let name = key.replace(['.', '-'], "_");
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// Creates the identifier of a method or argument for a key, escaping it if it is a keyword.
+fn ident(file: &LitStr, key: &str, name: &str) -> syn::Result<Ident> {
+    let error = |reason: String| {
+        syn::Error::new(
+            file.span(),
+            format!(
+                "`{key}` in {} can't be translated by a method: {reason}",
+                file.value()
+            ),
+        )
+    };
+    if let Ok(ident) = syn::parse_str::<Ident>(name) {
+        return Ok(ident);
+    }
+    // These keywords can't be escaped as raw identifiers
+    if ["self", "super", "crate", "Self", "_"].contains(&name) {
+        return Err(error(format!("`{name}` is a reserved name")));
+    }
+    syn::parse_str::<Ident>(&format!("r#{name}"))
+        .map_err(|_| error(format!("`{name}` isn't a valid identifier")))
+}
+
+/// Resolves the path of a file relative to the crate's `Cargo.toml`.
+fn file_path(file: &LitStr) -> syn::Result<PathBuf> {
+    let dir =
+        std::env::var("CARGO_MANIFEST_DIR").map_err(|err| syn::Error::new(file.span(), err))?;
+    Ok(PathBuf::from(dir).join(file.value()))
+}
+
+/// Reads the keys of a JSON or Fluent translation file.
+fn read_keys(file: &LitStr) -> syn::Result<Keys> {
+    let path = file_path(file)?;
+    let error = |message: String| syn::Error::new(file.span(), message);
+    let source = std::fs::read_to_string(&path)
+        .map_err(|err| error(format!("failed to read {}: {}", path.display(), err)))?;
+
+    let mut keys = Keys::new();
+    if file.value().ends_with(".ftl") {
+        let resource = fluent_syntax::parser::parse(source.as_str()).map_err(|(_, errors)| {
+            error(format!("failed to parse {}: {:?}", file.value(), errors))
+        })?;
+        for entry in resource.body {
+            if let Entry::Message(message) = entry {
+                if let Some(value) = &message.value {
+                    let mut params = BTreeSet::new();
+                    ftl_params(value, &mut params);
+                    keys.insert(message.id.name.to_string(), params);
+                }
+                for attribute in &message.attributes {
+                    let mut params = BTreeSet::new();
+                    ftl_params(&attribute.value, &mut params);
+                    keys.insert(format!("{}.{}", message.id.name, attribute.id.name), params);
+                }
+            }
+        }
+    } else {
+        let language: Value = serde_json::from_str(&source)
+            .map_err(|err| error(format!("failed to parse {}: {}", file.value(), err)))?;
+        let texts = language
+            .get("texts")
+            .ok_or_else(|| error(format!("{} has no `texts`", file.value())))?;
+        json_keys(texts, "", &mut keys).map_err(error)?;
+    }
+    Ok(keys)
+}
+
+/// Collects the keys of a JSON text tree.
+fn json_keys(text: &Value, path: &str, keys: &mut Keys) -> Result<(), String> {
+    match text {
+        Value::String(value) => {
+            keys.insert(path.to_string(), json_params(value));
+        }
+        Value::Object(texts) if is_plural(texts) => {
+            let mut params = BTreeSet::from([PLURAL_PARAM.to_string()]);
+            for value in texts.values().filter_map(Value::as_str) {
+                params.extend(json_params(value));
+            }
+            keys.insert(path.to_string(), params);
+        }
+        Value::Object(texts) => {
+            for (key, text) in texts {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                json_keys(text, &path, keys)?;
+            }
+        }
+        _ => return Err(format!("`{path}` is not a text")),
+    }
+    Ok(())
+}
+
+/// Whether an object is a plural text rather than a group of texts.
+fn is_plural(texts: &Map<String, Value>) -> bool {
+    texts.contains_key("other")
+        && texts
+            .iter()
+            .all(|(key, value)| PLURAL_CATEGORIES.contains(&key.as_str()) && value.is_string())
+}
+
+/// Collects the parameters of a JSON text in the ICU MessageFormat syntax.
+///
+/// These are the `{name}` and `{name, type, style}` arguments, including the ones in the cases of `select` and `plural` arguments.
+fn json_params(text: &str) -> BTreeSet<String> {
+    let mut params = BTreeSet::new();
+    message_params(text, &mut params);
+    params
+}
+
+fn message_params(text: &str, params: &mut BTreeSet<String>) {
+    let mut rest = text;
+    while let Some(start) = rest.find(['{', '\'']) {
+        if rest[start..].starts_with('\'') {
+            // `''` is an apostrophe and `'{...'` is quoted text
+            rest = &rest[start + 1..];
+            if let Some(after) = rest.strip_prefix('\'') {
+                rest = after;
+            } else if rest.starts_with(['{', '}']) {
+                rest = rest.find('\'').map_or("", |end| &rest[end + 1..]);
+            }
+            continue;
+        }
+
+        let Some(end) = closing_brace(&rest[start..]).map(|end| start + end) else {
+            break;
+        };
+        let mut fields = rest[start + 1..end].splitn(3, ',');
+        let name = fields.next().unwrap_or_default().trim();
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            params.insert(name.to_string());
+        }
+        if let (Some("select" | "plural" | "selectordinal"), Some(mut cases)) =
+            (fields.next().map(str::trim), fields.next())
+        {
+            // Each case is a `selector {message}`
+            while let Some(case_start) = cases.find('{') {
+                let Some(case_end) =
+                    closing_brace(&cases[case_start..]).map(|end| case_start + end)
+                else {
+                    break;
+                };
+                message_params(&cases[case_start + 1..case_end], params);
+                cases = &cases[case_end + 1..];
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+}
+
+/// Finds the `}` that closes the `{` a text starts with.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Collects the `$name` variables of a Fluent pattern.
+fn ftl_params(pattern: &Pattern<&str>, params: &mut BTreeSet<String>) {
+    for element in &pattern.elements {
+        if let PatternElement::Placeable { expression } = element {
+            ftl_expression_params(expression, params);
+        }
+    }
+}
+
+fn ftl_expression_params(expression: &Expression<&str>, params: &mut BTreeSet<String>) {
+    match expression {
+        Expression::Select { selector, variants } => {
+            ftl_inline_params(selector, params);
+            for variant in variants {
+                ftl_params(&variant.value, params);
+            }
+        }
+        Expression::Inline(inline) => ftl_inline_params(inline, params),
+    }
+}
+
+fn ftl_inline_params(inline: &InlineExpression<&str>, params: &mut BTreeSet<String>) {
+    match inline {
+        InlineExpression::VariableReference { id } => {
+            params.insert(id.name.to_string());
+        }
+        InlineExpression::FunctionReference { arguments, .. } => {
+            for argument in &arguments.positional {
+                ftl_inline_params(argument, params);
+            }
+            for argument in &arguments.named {
+                ftl_inline_params(&argument.value, params);
+            }
+        }
+        InlineExpression::Placeable { expression } => ftl_expression_params(expression, params),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+
+    fn expand_json(name: &str, texts: &str) -> syn::Result<String> {
+        let path = std::env::temp_dir().join(format!("dioxus-sdk-macro-{name}.json"));
+        std::fs::write(&path, format!(r#"{{ "id": "en-US", "texts": {texts} }}"#)).unwrap();
+        let file = LitStr::new(&path.to_string_lossy(), Span::call_site());
+        let input = syn::parse2(quote! { pub Texts, #file }).unwrap();
+        expand(input).map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn generates_methods() {
+        let tokens = expand_json(
+            "methods",
+            r#"{ "messages": { "hello": "Hello {name}" }, "fn": "Function" }"#,
+        )
+        .unwrap();
+
+        let hello = quote! {
+            #[doc = "Translates `messages.hello`."]
+            pub fn messages_hello(&self, name: impl ::std::string::ToString) -> ::std::string::String {
+                #[allow(unused_mut)]
+                let mut params = ::std::collections::HashMap::new();
+                params.insert("name", ::std::string::ToString::to_string(&name));
+                self.i18.translate_with_params("messages.hello", params)
+            }
+        };
+        assert!(tokens.contains(&hello.to_string()), "{tokens}");
+        assert!(tokens.contains("pub fn r#fn (& self ,)"), "{tokens}");
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        for (name, texts, message) in [
+            (
+                "space",
+                r#"{ "hello world": "Hello" }"#,
+                "`hello world` isn't a valid identifier",
+            ),
+            ("self", r#"{ "self": "Me" }"#, "`self` is a reserved name"),
+            (
+                "symbol",
+                r#"{ "price€": "Price" }"#,
+                "`price€` isn't a valid identifier",
+            ),
+        ] {
+            let err = expand_json(name, texts).unwrap_err();
+            assert!(err.to_string().ends_with(message), "{err}");
+        }
+    }
+
+    #[test]
+    fn rejects_colliding_keys() {
+        let err =
+            expand_json("collision", r#"{ "a": { "b": "Dot" }, "a-b": "Dash" }"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`a-b` and `a.b` would both be translated by the method `a_b`, rename one of them"
+        );
+    }
+}
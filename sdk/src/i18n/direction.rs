@@ -0,0 +1,137 @@
+//! Text direction of languages.
+
+use dioxus::prelude::*;
+use std::fmt;
+use unic_langid::{CharacterDirection, LanguageIdentifier};
+
+use super::use_i18n::{use_i18, UseI18};
+
+/// The direction text is written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextDirection {
+    /// Left to right, e.g. English.
+    #[default]
+    Ltr,
+    /// Right to left, e.g. Arabic and Hebrew.
+    Rtl,
+}
+
+impl TextDirection {
+    /// Gets the value of the HTML `dir` attribute for this direction.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextDirection::Ltr => "ltr",
+            TextDirection::Rtl => "rtl",
+        }
+    }
+}
+
+impl fmt::Display for TextDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Gets the direction a language is written in, from its script or, if it has none, from the language.
+pub fn text_direction(language: &LanguageIdentifier) -> TextDirection {
+    match language.character_direction() {
+        CharacterDirection::RTL => TextDirection::Rtl,
+        // Newer versions also know top to bottom scripts, which browsers lay out left to right
+        #[allow(unreachable_patterns)]
+        _ => TextDirection::Ltr,
+    }
+}
+
+/// Isolates a text from the text around it, so its direction doesn't affect the order of the surrounding text.
+///
+/// Parameters of texts in right-to-left languages are isolated automatically.
+pub fn bidi_isolate(text: &str) -> String {
+    format!("\u{2068}{text}\u{2069}")
+}
+
+impl UseI18 {
+    /// Gets the direction of the selected language.
+    pub fn direction(&self) -> TextDirection {
+        text_direction(&self.selected_language.read())
+    }
+}
+
+/// A hook that keeps the `lang` and `dir` attributes of the document root in sync with the selected language.
+///
+/// This works on web and in the desktop webview.
+///
+/// # Example
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::i18n::{use_document_language, use_init_i18n};
+///
+/// fn App() -> Element {
+///     use_init_i18n("ar-EG".parse().unwrap(), "en-US".parse().unwrap(), Vec::new);
+///     use_document_language();
+///
+///     rsx! { Body {} }
+/// }
+///
+/// #[component]
+/// fn Body() -> Element {
+///     rsx! { p { "مرحبا" } }
+/// }
+/// ```
+pub fn use_document_language() {
+    let i18 = use_i18();
+    use_effect(move || {
+        let language = i18.selected_language.read();
+        set_document_language(&language.to_string(), text_direction(&language));
+    });
+}
+
+// Web implementation of the document attributes setter.
+#[cfg(target_family = "wasm")]
+fn set_document_language(language: &str, direction: TextDirection) {
+    let Some(root) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.document_element())
+    else {
+        return;
+    };
+    root.set_attribute("lang", language).ok();
+    root.set_attribute("dir", direction.as_str()).ok();
+}
+
+// Desktop implementation of the document attributes setter, the document lives in the webview.
+#[cfg(not(target_family = "wasm"))]
+fn set_document_language(language: &str, direction: TextDirection) {
+    eval(&format!(
+        "document.documentElement.lang = {:?}; document.documentElement.dir = {:?};",
+        language,
+        direction.as_str()
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_direction() {
+        let direction = |id: &str| text_direction(&id.parse().unwrap());
+        assert_eq!(direction("en-US"), TextDirection::Ltr);
+        assert_eq!(direction("ar-EG"), TextDirection::Rtl);
+        assert_eq!(direction("he"), TextDirection::Rtl);
+        assert_eq!(direction("az-Arab"), TextDirection::Rtl);
+    }
+
+    #[test]
+    fn isolates_params_in_rtl_texts() {
+        let language = crate::i18n::Language::from_json(
+            r#"{ "id": "he", "texts": { "hello": "שלום {name}!" } }"#,
+        )
+        .unwrap();
+        let params = std::collections::HashMap::from([("name", "Dioxus".to_string())]);
+        assert_eq!(
+            language.get_text("hello", params).unwrap(),
+            "שלום \u{2068}Dioxus\u{2069}!"
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! Support for [Project Fluent](https://projectfluent.org/) (`.ftl`) translation resources.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::resolver::{errors::ReferenceKind, ResolverError};
+use fluent_bundle::{FluentArgs, FluentError, FluentResource, FluentValue};
+use fluent_syntax::ast::{
+    CallArguments, Entry, Expression, Identifier, InlineExpression, Pattern, PatternElement,
+    Resource, Variant, VariantKey,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+use unic_langid::LanguageIdentifier;
+
+use super::direction::{text_direction, TextDirection};
+use super::plural::{category_name, plural_category};
+use super::pseudo::PseudoLocale;
+use super::use_i18n::{Language, LanguageError, Text};
+
+/// The messages of a parsed Fluent resource.
+///
+/// The bundles are shared between threads, so languages can be loaded and sent like the other formats.
+#[derive(Clone)]
+pub(crate) struct FluentMessages {
+    bundle: Arc<FluentBundle<FluentResource>>,
+    /// The paths of all messages and attributes in the resource.
+    keys: Arc<Vec<String>>,
+    /// The source of the resource, to serialize it and to create the bundles of pseudo-locales from.
+    source: Arc<str>,
+    /// The bundles that transform the texts of the messages for a pseudo-locale, created when they are first used.
+    pseudo_bundles: Arc<Mutex<HashMap<PseudoLocale, Arc<FluentBundle<FluentResource>>>>>,
+}
+
+impl fmt::Debug for FluentMessages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FluentMessages")
+            .field("locales", &self.bundle.locales)
+            .finish()
+    }
+}
+
+impl FluentMessages {
+    fn new(id: &LanguageIdentifier, source: &str) -> Result<Self, LanguageError> {
+        let resource = parse(source)?;
+
+        let mut keys = Vec::new();
+        for entry in resource.entries() {
+            if let Entry::Message(message) = entry {
+                if message.value.is_some() {
+                    keys.push(message.id.name.to_string());
+                }
+                for attribute in &message.attributes {
+                    keys.push(format!("{}.{}", message.id.name, attribute.id.name));
+                }
+            }
+        }
+
+        Ok(Self {
+            bundle: Arc::new(bundle(id, resource, None)?),
+            keys: Arc::new(keys),
+            source: source.into(),
+            pseudo_bundles: Arc::default(),
+        })
+    }
+
+    /// Gets the bundle that transforms the texts of the messages for the pseudo-locale.
+    fn pseudo_bundle(
+        &self,
+        pseudo_locale: PseudoLocale,
+    ) -> Option<Arc<FluentBundle<FluentResource>>> {
+        let mut pseudo_bundles = self.pseudo_bundles.lock().ok()?;
+        if let Some(bundle) = pseudo_bundles.get(&pseudo_locale) {
+            return Some(bundle.clone());
+        }
+        // The source was already parsed successfully, so this only fails if Fluent changes its mind
+        let resource = parse(&self.source).ok()?;
+        let transform: fn(&str) -> Cow<str> = match pseudo_locale {
+            PseudoLocale::Accented => |text| Cow::Owned(PseudoLocale::Accented.transform(text)),
+            PseudoLocale::Bidi => |text| Cow::Owned(PseudoLocale::Bidi.transform(text)),
+        };
+        let id = self.bundle.locales.first()?;
+        let mut bundle = bundle(id, resource, Some(transform)).ok()?;
+        // The texts are laid out in the direction of the pseudo-locale, not in that of the language they are generated from
+        bundle.set_use_isolating(pseudo_locale.direction() == TextDirection::Rtl);
+        let bundle = Arc::new(bundle);
+        pseudo_bundles.insert(pseudo_locale, bundle.clone());
+        Some(bundle)
+    }
+
+    /// Gets the paths of all messages and attributes.
+    pub(crate) fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// Formats the message or attribute at the given path.
+    ///
+    /// The path is either a message ID (`hello-world`) or a message ID followed by an attribute (`login-input.placeholder`).
+    /// Terms are resolved when messages reference them.
+    /// Variables the message references that aren't in the parameters are added to `missing_params`.
+    /// With a pseudo-locale the texts of the message are transformed, but not the values of the variables.
+    pub(crate) fn format(
+        &self,
+        path: &str,
+        params: &HashMap<&str, String>,
+        pseudo_locale: Option<PseudoLocale>,
+        missing_params: &mut Vec<String>,
+    ) -> Option<String> {
+        let bundle = match pseudo_locale {
+            Some(pseudo_locale) => self.pseudo_bundle(pseudo_locale)?,
+            None => self.bundle.clone(),
+        };
+        let (message_id, attribute) = match path.split_once('.') {
+            Some((message_id, attribute)) => (message_id, Some(attribute)),
+            None => (path, None),
+        };
+
+        let message = bundle.get_message(message_id)?;
+        let pattern = match attribute {
+            Some(attribute) => message.get_attribute(attribute)?.value(),
+            None => message.value()?,
+        };
+
+        // Parameters that look like numbers are passed as numbers so Fluent can format them and select plural variants.
+        let mut args = FluentArgs::new();
+        for (name, value) in params {
+            args.set(*name, FluentValue::try_number(value.as_str()));
+        }
+
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, Some(&args), &mut errors);
+        for error in errors {
+            match error {
+                FluentError::ResolverError(ResolverError::Reference(ReferenceKind::Variable {
+                    id,
+                })) => missing_params.push(id),
+                error => tracing::warn!("Error formatting \"{}\": {}", path, error),
+            }
+        }
+        Some(text.into_owned())
+    }
+}
+
+/// The function that selects the plural category of numbers in the select expressions of resources, see [`select_plurals`].
+const PLURAL_FUNCTION: &str = "PLURAL";
+
+/// The plural categories that variants can be named after.
+const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+fn parse(source: &str) -> Result<FluentResource, LanguageError> {
+    let parse_error = |errors: Vec<fluent_syntax::parser::ParserError>| {
+        LanguageError::Fluent(errors.into_iter().map(FluentError::ParserError).collect())
+    };
+    let mut resource = fluent_syntax::parser::parse(source.to_string())
+        .map_err(|(_, errors)| parse_error(errors))?;
+    select_plurals(&mut resource);
+    FluentResource::try_new(fluent_syntax::serializer::serialize(&resource))
+        .map_err(|(_, errors)| parse_error(errors))
+}
+
+/// Makes the select expressions of a resource select plural categories with the same plural rules as the other formats.
+///
+/// Fluent would select them with plural rules of its own, so the categories are selected by [`PLURAL_FUNCTION`] instead,
+/// after the variants for exact numbers:
+/// `{ $count -> [0] none [one] one *[other] many }` becomes `{ $count -> [0] none *[plural] { PLURAL($count) -> [one] one *[other] many } }`.
+fn select_plurals(resource: &mut Resource<String>) {
+    for entry in &mut resource.body {
+        let (value, attributes) = match entry {
+            Entry::Message(message) => (message.value.as_mut(), &mut message.attributes),
+            Entry::Term(term) => (Some(&mut term.value), &mut term.attributes),
+            _ => continue,
+        };
+        let attributes = attributes.iter_mut().map(|attribute| &mut attribute.value);
+        for pattern in value.into_iter().chain(attributes) {
+            select_plurals_in_pattern(pattern);
+        }
+    }
+}
+
+fn select_plurals_in_pattern(pattern: &mut Pattern<String>) {
+    for element in &mut pattern.elements {
+        if let PatternElement::Placeable { expression } = element {
+            select_plurals_in_expression(expression);
+        }
+    }
+}
+
+fn select_plurals_in_expression(expression: &mut Expression<String>) {
+    let (selector, variants) = match expression {
+        Expression::Select { selector, variants } => (selector, variants),
+        Expression::Inline(InlineExpression::Placeable { expression }) => {
+            return select_plurals_in_expression(expression);
+        }
+        Expression::Inline(_) => return,
+    };
+    for variant in variants.iter_mut() {
+        select_plurals_in_pattern(&mut variant.value);
+    }
+
+    // Only numbers are selected by their plural category, strings such as the attributes of terms are compared as they are
+    let selects_number = matches!(
+        selector,
+        InlineExpression::VariableReference { .. }
+            | InlineExpression::NumberLiteral { .. }
+            | InlineExpression::FunctionReference { .. }
+    );
+    let selects_categories = variants.iter().any(|variant| {
+        matches!(&variant.key, VariantKey::Identifier { name } if PLURAL_CATEGORIES.contains(&name.as_str()))
+    });
+    if !selects_number || !selects_categories {
+        return;
+    }
+
+    let (mut numbers, mut categories): (Vec<_>, Vec<_>) = std::mem::take(variants)
+        .into_iter()
+        .partition(|variant| matches!(variant.key, VariantKey::NumberLiteral { .. }));
+    // A number that is the default variant is still used when no category matches
+    if let Some(default) = numbers.iter_mut().find(|variant| variant.default) {
+        categories.push(default.clone());
+        default.default = false;
+    }
+    let plural = Expression::Select {
+        selector: InlineExpression::FunctionReference {
+            id: Identifier {
+                name: PLURAL_FUNCTION.to_string(),
+            },
+            arguments: CallArguments {
+                positional: vec![selector.clone()],
+                named: Vec::new(),
+            },
+        },
+        variants: categories,
+    };
+    if numbers.is_empty() {
+        *expression = plural;
+        return;
+    }
+    numbers.push(Variant {
+        key: VariantKey::Identifier {
+            name: "plural".to_string(),
+        },
+        value: Pattern {
+            elements: vec![PatternElement::Placeable { expression: plural }],
+        },
+        default: true,
+    });
+    *variants = numbers;
+}
+
+fn bundle(
+    id: &LanguageIdentifier,
+    resource: FluentResource,
+    transform: Option<fn(&str) -> Cow<str>>,
+) -> Result<FluentBundle<FluentResource>, LanguageError> {
+    let mut bundle = FluentBundle::new_concurrent(vec![id.clone()]);
+    // Parameters are only isolated in right-to-left languages, the same way as for JSON languages.
+    bundle.set_use_isolating(text_direction(id) == TextDirection::Rtl);
+    bundle.set_transform(transform);
+    let language = id.clone();
+    bundle
+        .add_function(PLURAL_FUNCTION, move |positional, _| {
+            match positional.first() {
+                Some(FluentValue::Number(number)) => {
+                    let category = plural_category(&language, &number.as_string());
+                    FluentValue::from(category.map_or("other", category_name))
+                }
+                Some(value) => value.clone(),
+                None => FluentValue::None,
+            }
+        })
+        .map_err(|error| LanguageError::Fluent(vec![error]))?;
+    bundle
+        .add_resource(resource)
+        .map_err(LanguageError::Fluent)?;
+    Ok(bundle)
+}
+
+/// A language as it's serialized, with the source of its Fluent resource so that it can be parsed again.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct SerializedLanguage {
+    id: LanguageIdentifier,
+    texts: Text,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ftl: Option<String>,
+}
+
+impl TryFrom<SerializedLanguage> for Language {
+    type Error = LanguageError;
+
+    fn try_from(language: SerializedLanguage) -> Result<Self, Self::Error> {
+        let fluent = language
+            .ftl
+            .map(|source| FluentMessages::new(&language.id, &source))
+            .transpose()?;
+        Ok(Self {
+            id: language.id,
+            texts: language.texts,
+            fluent,
+        })
+    }
+}
+
+impl From<Language> for SerializedLanguage {
+    fn from(language: Language) -> Self {
+        Self {
+            id: language.id,
+            texts: language.texts,
+            ftl: language.fluent.map(|fluent| fluent.source.to_string()),
+        }
+    }
+}
+
+impl Language {
+    /// Creates a language from the source of a Fluent (`.ftl`) resource.
+    ///
+    /// ```rust
+    /// use dioxus_sdk::i18n::Language;
+    ///
+    /// let language = Language::from_ftl(
+    ///     "en-US".parse().unwrap(),
+    ///     "hello = Hello {$name}\nlogin = Log in\n    .title = Log in to your account",
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn from_ftl(id: LanguageIdentifier, source: &str) -> Result<Self, LanguageError> {
+        let fluent = FluentMessages::new(&id, source)?;
+        Ok(Self {
+            id,
+            texts: Text::default(),
+            fluent: Some(fluent),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+-brand = Dioxus
+    .gender = neuter
+hello = Hello { $name }
+welcome = Welcome to { -brand }
+brand-is = { -brand.gender ->
+    [masculine] He is { -brand }
+   *[other] It is { -brand }
+}
+files = { $count ->
+    [0] No files
+    [one] { $count } file
+   *[other] { $count } files
+}
+login = Log in
+    .title = Log in to your account
+"#;
+
+    fn format(language: &Language, path: &str, params: &[(&str, &str)]) -> Option<String> {
+        let params = params
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .collect();
+        language.format_text(path, params, None, &mut Vec::new())
+    }
+
+    #[test]
+    fn formats_messages() {
+        let language = Language::from_ftl("en-US".parse().unwrap(), SOURCE).unwrap();
+
+        assert_eq!(
+            format(&language, "hello", &[("name", "Ana")]).as_deref(),
+            Some("Hello Ana")
+        );
+        // Attributes are keys of their own, and terms are resolved in messages
+        assert_eq!(
+            format(&language, "login.title", &[]).as_deref(),
+            Some("Log in to your account")
+        );
+        assert_eq!(
+            format(&language, "welcome", &[]).as_deref(),
+            Some("Welcome to Dioxus")
+        );
+        assert_eq!(
+            format(&language, "brand-is", &[]).as_deref(),
+            Some("It is Dioxus")
+        );
+        // Terms can't be translated on their own
+        assert_eq!(format(&language, "-brand", &[]), None);
+        assert_eq!(format(&language, "missing", &[]), None);
+    }
+
+    #[test]
+    fn formats_arguments() {
+        let language = Language::from_ftl("en-US".parse().unwrap(), SOURCE).unwrap();
+
+        // Numbers are selected exactly first, then by their plural category, and keep their fraction digits
+        assert_eq!(
+            format(&language, "files", &[("count", "0")]).as_deref(),
+            Some("No files")
+        );
+        assert_eq!(
+            format(&language, "files", &[("count", "1")]).as_deref(),
+            Some("1 file")
+        );
+        assert_eq!(
+            format(&language, "files", &[("count", "1.50")]).as_deref(),
+            Some("1.50 files")
+        );
+
+        // Parameters are only isolated in right-to-left languages
+        let arabic = Language::from_ftl("ar".parse().unwrap(), "hello = مرحبا { $name }").unwrap();
+        assert_eq!(
+            format(&arabic, "hello", &[("name", "Ana")]).as_deref(),
+            Some("مرحبا \u{2068}Ana\u{2069}")
+        );
+    }
+
+    #[test]
+    fn serializes_the_source() {
+        let language = Language::from_ftl("en-US".parse().unwrap(), SOURCE).unwrap();
+
+        let json = serde_json::to_string(&language).unwrap();
+        let language = Language::from_json(&json).unwrap();
+        assert_eq!(
+            format(&language, "login.title", &[]).as_deref(),
+            Some("Log in to your account")
+        );
+
+        let invalid = r#"{ "id": "en-US", "texts": {}, "ftl": "hello = {" }"#;
+        assert!(Language::from_json(invalid).is_err());
+    }
+
+    #[test]
+    fn languages_are_send() {
+        fn assert_send<T: Send + Sync>() {}
+        assert_send::<Language>();
+    }
+}
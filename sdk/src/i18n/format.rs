@@ -0,0 +1,571 @@
+//! Locale-aware formatting of numbers, dates and lists.
+
+use chrono::{Datelike, Offset, TimeZone, Timelike};
+use dioxus::prelude::*;
+use fixed_decimal::FixedDecimal;
+use icu::calendar::{AnyCalendar, DateTime};
+use icu::datetime::{options::length, DateTimeFormatter, ZonedDateTimeFormatter};
+use icu::decimal::FixedDecimalFormatter;
+use icu::experimental::dimension::currency::formatter::CurrencyCode;
+use icu::experimental::dimension::provider::currency::{
+    CurrencyEssentialsV1Marker, PatternSelection, PlaceholderValue,
+};
+use icu::experimental::dimension::provider::percent::PercentEssentialsV1Marker;
+use icu::experimental::provider::Baked;
+use icu::experimental::relativetime::RelativeTimeFormatter;
+use icu::list::{ListFormatter, ListLength};
+use icu::locid::Locale;
+use icu::timezone::{CustomTimeZone, GmtOffset};
+use icu_provider::{DataLocale, DataPayload, DataProvider, DataRequest, KeyedDataMarker};
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::rc::Rc;
+use unic_langid::LanguageIdentifier;
+use writeable::Writeable;
+
+use super::use_i18n::UseI18;
+
+/// How much detail a formatted date or time includes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormatLength {
+    /// `5.3.24`, `14:07`
+    Short,
+    /// `05.03.2024`, `14:07:09`
+    #[default]
+    Medium,
+    /// `5. März 2024`, `14:07:09 GMT+2`
+    Long,
+    /// `Dienstag, 5. März 2024`, `14:07:09 GMT+02:00`
+    Full,
+}
+
+/// The unit of a relative time, see [`format_relative_time`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelativeTimeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+/// How the items of a list are joined, see [`format_list`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListStyle {
+    /// `a, b und c`
+    #[default]
+    And,
+    /// `a, b oder c`
+    Or,
+    /// A list of measurements, `3 Std., 5 Min.`
+    Unit,
+}
+
+impl From<FormatLength> for length::Date {
+    fn from(value: FormatLength) -> Self {
+        match value {
+            FormatLength::Short => length::Date::Short,
+            FormatLength::Medium => length::Date::Medium,
+            FormatLength::Long => length::Date::Long,
+            FormatLength::Full => length::Date::Full,
+        }
+    }
+}
+
+impl From<FormatLength> for length::Time {
+    fn from(value: FormatLength) -> Self {
+        match value {
+            FormatLength::Short => length::Time::Short,
+            FormatLength::Medium => length::Time::Medium,
+            FormatLength::Long => length::Time::Long,
+            FormatLength::Full => length::Time::Full,
+        }
+    }
+}
+
+impl FormatLength {
+    fn parse(style: Option<&str>) -> Option<Self> {
+        match style {
+            None => Some(Self::default()),
+            Some("short") => Some(Self::Short),
+            Some("medium") => Some(Self::Medium),
+            Some("long") => Some(Self::Long),
+            Some("full") => Some(Self::Full),
+            Some(_) => None,
+        }
+    }
+}
+
+/// The number of digits after the decimal separator of the currency with the given ISO 4217 code.
+///
+/// These are the minor units of ISO 4217, ICU4X doesn't provide them yet. Currencies that aren't listed have two.
+fn fraction_digits(currency: &str) -> i16 {
+    match currency {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
+
+/// The formatters of every language and options they were needed for, or `None` when there is no data for them.
+type FormatterCache = HashMap<(TypeId, LanguageIdentifier, String), Option<Rc<dyn Any>>>;
+
+thread_local! {
+    // Formatters are created once per language and options, as creating one loads its data.
+    static FORMATTERS: RefCell<FormatterCache> = RefCell::default();
+}
+
+/// Gets the formatter of a type for the given language and options, creating it the first time it's needed.
+fn formatter<F: 'static>(
+    language: &LanguageIdentifier,
+    options: impl Debug,
+    create: impl FnOnce(&DataLocale) -> Option<F>,
+) -> Option<Rc<F>> {
+    let key = (TypeId::of::<F>(), language.clone(), format!("{options:?}"));
+    let cached = FORMATTERS.with(|formatters| formatters.borrow().get(&key).cloned());
+    let formatter = match cached {
+        Some(formatter) => formatter,
+        None => {
+            let locale: Locale = language.to_string().parse().unwrap_or_default();
+            let formatter =
+                create(&(&locale).into()).map(|formatter| Rc::new(formatter) as Rc<dyn Any>);
+            FORMATTERS.with(|formatters| formatters.borrow_mut().insert(key, formatter.clone()));
+            formatter
+        }
+    };
+    formatter?.downcast().ok()
+}
+
+/// Loads data that ICU4X has no formatter for yet.
+fn data<M: KeyedDataMarker>(language: &LanguageIdentifier) -> Option<Rc<DataPayload<M>>>
+where
+    Baked: DataProvider<M>,
+{
+    formatter(language, M::KEY, |locale| {
+        Baked
+            .load(DataRequest {
+                locale,
+                metadata: Default::default(),
+            })
+            .ok()?
+            .take_payload()
+            .ok()
+    })
+}
+
+fn decimal(value: f64) -> Option<FixedDecimal> {
+    // `Display` for floats never uses an exponent, so the output can always be parsed unless it isn't finite.
+    value.to_string().parse().ok()
+}
+
+fn format_decimal(language: &LanguageIdentifier, value: &FixedDecimal) -> String {
+    let formatter = formatter(language, (), |locale| {
+        FixedDecimalFormatter::try_new(locale, Default::default()).ok()
+    });
+    match formatter {
+        Some(formatter) => formatter.format_to_string(value),
+        None => value.to_string(),
+    }
+}
+
+/// Formats a number with the decimal and grouping separators of a language, e.g. `1.234.567,5` in German.
+pub fn format_number(language: &LanguageIdentifier, value: f64) -> String {
+    match decimal(value) {
+        Some(value) => format_decimal(language, &value),
+        None => value.to_string(),
+    }
+}
+
+/// Formats a ratio as a percentage, e.g. `0.25` as `25 %` in German.
+pub fn format_percent(language: &LanguageIdentifier, value: f64) -> String {
+    let Some(mut value) = decimal(value) else {
+        return value.to_string();
+    };
+    value.multiply_pow10(2);
+    value.trim_start();
+    let number = format_decimal(language, &value);
+    let Some(essentials) = data::<PercentEssentialsV1Marker>(language) else {
+        return format!("{number}%");
+    };
+    let essentials = essentials.get();
+
+    // The pattern has the number as `#,##0` and the percent sign as `%`, e.g. `#,##0 %`. Negative numbers use the same pattern here.
+    let pattern = essentials.standard.split(';').next().unwrap_or_default();
+    let mut number = Some(number);
+    let mut formatted = String::new();
+    for char in pattern.chars() {
+        match char {
+            '#' | '0' | ',' | '.' => formatted.extend(number.take()),
+            '%' => formatted.push_str(&essentials.percent_sign_symbol),
+            char => formatted.push(char),
+        }
+    }
+    formatted
+}
+
+/// Formats an amount of money in the currency with the given ISO 4217 code, e.g. `1.234,50 €` for `EUR` in German.
+pub fn format_currency(language: &LanguageIdentifier, value: f64, currency: &str) -> String {
+    let Some(mut value) = decimal(value) else {
+        return format!("{value} {currency}");
+    };
+    let code = match currency.parse() {
+        Ok(code) => CurrencyCode(code),
+        Err(_) => return format!("{} {}", format_decimal(language, &value), currency),
+    };
+    let fraction_digits = fraction_digits(currency);
+    value.half_even(-fraction_digits);
+    value.pad_end(-fraction_digits);
+    let number = format_decimal(language, &value);
+
+    // The currency formatter of ICU4X doesn't localize the number yet, so its pattern is filled with the number formatted above.
+    let Some(essentials) = data::<CurrencyEssentialsV1Marker>(language) else {
+        return format!("{number} {currency}");
+    };
+    let essentials = essentials.get();
+    let config = essentials
+        .pattern_config_map
+        .get_copied(&code.0.to_unvalidated())
+        .unwrap_or(essentials.default_pattern_config);
+    let sign = match config.short_placeholder_value {
+        Some(PlaceholderValue::Index(index)) => essentials.placeholders.get(index.into()),
+        Some(PlaceholderValue::ISO) | None => None,
+    };
+    let pattern = match config.short_pattern_selection {
+        PatternSelection::Standard => essentials.standard_pattern.as_ref(),
+        PatternSelection::StandardAlphaNextToNumber => {
+            essentials.standard_alpha_next_to_number_pattern.as_ref()
+        }
+    };
+    match pattern {
+        Some(pattern) => pattern
+            .interpolate((number.as_str(), sign.unwrap_or(currency)))
+            .write_to_string()
+            .into_owned(),
+        None => format!("{number} {currency}"),
+    }
+}
+
+fn datetime<Tz: TimeZone>(value: &chrono::DateTime<Tz>) -> Option<DateTime<AnyCalendar>> {
+    let local = value.naive_local();
+    let datetime = DateTime::try_new_iso_datetime(
+        local.year(),
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+    )
+    .ok()?;
+    Some(datetime.to_any())
+}
+
+fn time_zone<Tz: TimeZone>(value: &chrono::DateTime<Tz>) -> Option<CustomTimeZone> {
+    let offset = GmtOffset::try_from_offset_seconds(value.offset().fix().local_minus_utc()).ok()?;
+    Some(CustomTimeZone::new_with_offset(offset))
+}
+
+fn format_zoned<Tz: TimeZone>(
+    language: &LanguageIdentifier,
+    value: &chrono::DateTime<Tz>,
+    bag: length::Bag,
+) -> Option<String> {
+    let formatter = formatter(language, bag, |locale| {
+        ZonedDateTimeFormatter::try_new(locale, bag.into(), Default::default()).ok()
+    })?;
+    formatter
+        .format_to_string(&datetime(value)?, &time_zone(value)?)
+        .ok()
+}
+
+/// Formats the date of a date-time in its time zone, e.g. `05.03.2024` in German.
+pub fn format_date<Tz: TimeZone>(
+    language: &LanguageIdentifier,
+    value: &chrono::DateTime<Tz>,
+    length: FormatLength,
+) -> String {
+    let bag = length::Bag::from_date_style(length.into());
+    let formatted = formatter(language, bag, |locale| {
+        DateTimeFormatter::try_new(locale, bag.into()).ok()
+    })
+    .and_then(|formatter| formatter.format_to_string(&datetime(value)?).ok());
+    formatted.unwrap_or_else(|| value.naive_local().date().to_string())
+}
+
+/// Formats the time of a date-time in its time zone, e.g. `14:07:09` in German.
+///
+/// The [`Long`](FormatLength::Long) and [`Full`](FormatLength::Full) lengths include the time zone.
+/// Time zones are always shown as their offset from GMT (`GMT+2`), as the date-time only has the offset and not the name of its zone.
+pub fn format_time<Tz: TimeZone>(
+    language: &LanguageIdentifier,
+    value: &chrono::DateTime<Tz>,
+    length: FormatLength,
+) -> String {
+    format_zoned(language, value, length::Bag::from_time_style(length.into()))
+        .unwrap_or_else(|| value.naive_local().time().to_string())
+}
+
+/// Formats a date-time in its time zone, e.g. `05.03.2024, 14:07:09` in German.
+///
+/// The [`Long`](FormatLength::Long) and [`Full`](FormatLength::Full) time lengths include the time zone, as its offset from GMT like in [`format_time`].
+pub fn format_date_time<Tz: TimeZone>(
+    language: &LanguageIdentifier,
+    value: &chrono::DateTime<Tz>,
+    date_length: FormatLength,
+    time_length: FormatLength,
+) -> String {
+    let bag = length::Bag::from_date_time_style(date_length.into(), time_length.into());
+    format_zoned(language, value, bag).unwrap_or_else(|| value.naive_local().to_string())
+}
+
+/// Formats a time relative to now, e.g. `-3` minutes as `vor 3 Minuten` and `2` days as `übermorgen` in German.
+pub fn format_relative_time(
+    language: &LanguageIdentifier,
+    value: i64,
+    unit: RelativeTimeUnit,
+) -> String {
+    let formatter = formatter(language, unit, |locale| {
+        let options = Default::default();
+        match unit {
+            RelativeTimeUnit::Second => RelativeTimeFormatter::try_new_long_second(locale, options),
+            RelativeTimeUnit::Minute => RelativeTimeFormatter::try_new_long_minute(locale, options),
+            RelativeTimeUnit::Hour => RelativeTimeFormatter::try_new_long_hour(locale, options),
+            RelativeTimeUnit::Day => RelativeTimeFormatter::try_new_long_day(locale, options),
+            RelativeTimeUnit::Week => RelativeTimeFormatter::try_new_long_week(locale, options),
+            RelativeTimeUnit::Month => RelativeTimeFormatter::try_new_long_month(locale, options),
+            RelativeTimeUnit::Quarter => {
+                RelativeTimeFormatter::try_new_long_quarter(locale, options)
+            }
+            RelativeTimeUnit::Year => RelativeTimeFormatter::try_new_long_year(locale, options),
+        }
+        .ok()
+    });
+    match formatter {
+        Some(formatter) => formatter
+            .format(FixedDecimal::from(value))
+            .write_to_string()
+            .into_owned(),
+        None => format!("{value} {unit:?}"),
+    }
+}
+
+/// Joins the items of a list, e.g. `a, b und c` in German.
+pub fn format_list<T: Display>(
+    language: &LanguageIdentifier,
+    items: impl IntoIterator<Item = T>,
+    style: ListStyle,
+) -> String {
+    let items = items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>();
+    let formatter = formatter(language, style, |locale| {
+        match style {
+            ListStyle::And => ListFormatter::try_new_and_with_length(locale, ListLength::Wide),
+            ListStyle::Or => ListFormatter::try_new_or_with_length(locale, ListLength::Wide),
+            ListStyle::Unit => ListFormatter::try_new_unit_with_length(locale, ListLength::Wide),
+        }
+        .ok()
+    });
+    match formatter {
+        Some(formatter) => formatter.format_to_string(items.iter()),
+        None => items.join(", "),
+    }
+}
+
+/// Formats the value of a `{name, type, style}` placeholder.
+///
+/// Returns `None` if the type or style isn't supported or the value can't be parsed.
+pub(crate) fn format_param(
+    language: &LanguageIdentifier,
+    value: &str,
+    format: &str,
+) -> Option<String> {
+    let (kind, style) = match format.split_once(',') {
+        Some((kind, style)) => (kind.trim(), Some(style.trim())),
+        None => (format.trim(), None),
+    };
+    match kind {
+        "number" => {
+            let number = value.trim().parse::<f64>().ok()?;
+            match style {
+                None => Some(format_number(language, number)),
+                Some("integer") => Some(format_number(language, number.round())),
+                Some("percent") => Some(format_percent(language, number)),
+                Some(style) => {
+                    let currency = style.strip_prefix("::currency/")?;
+                    Some(format_currency(language, number, currency))
+                }
+            }
+        }
+        "date" => Some(format_date(
+            language,
+            &parse_date_time(value)?,
+            FormatLength::parse(style)?,
+        )),
+        "time" => Some(format_time(
+            language,
+            &parse_date_time(value)?,
+            FormatLength::parse(style)?,
+        )),
+        _ => None,
+    }
+}
+
+/// Parses a date-time in RFC 3339 or in the format chrono displays date-times in.
+fn parse_date_time(value: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    let value = value.trim();
+    if let Ok(value) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(value);
+    }
+    if let Ok(value) = chrono::DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f %:z") {
+        return Some(value);
+    }
+    let utc = value.strip_suffix(" UTC")?;
+    let value = chrono::NaiveDateTime::parse_from_str(utc, "%Y-%m-%d %H:%M:%S%.f").ok()?;
+    Some(value.and_utc().fixed_offset())
+}
+
+impl UseI18 {
+    /// Formats a number in the selected language, see [`format_number`].
+    pub fn format_number(&self, value: f64) -> String {
+        format_number(&self.selected_language.read(), value)
+    }
+
+    /// Formats a ratio as a percentage in the selected language, see [`format_percent`].
+    pub fn format_percent(&self, value: f64) -> String {
+        format_percent(&self.selected_language.read(), value)
+    }
+
+    /// Formats an amount of money in the selected language, see [`format_currency`].
+    pub fn format_currency(&self, value: f64, currency: &str) -> String {
+        format_currency(&self.selected_language.read(), value, currency)
+    }
+
+    /// Formats a date in the selected language, see [`format_date`].
+    pub fn format_date<Tz: TimeZone>(
+        &self,
+        value: &chrono::DateTime<Tz>,
+        length: FormatLength,
+    ) -> String {
+        format_date(&self.selected_language.read(), value, length)
+    }
+
+    /// Formats a time in the selected language, see [`format_time`].
+    pub fn format_time<Tz: TimeZone>(
+        &self,
+        value: &chrono::DateTime<Tz>,
+        length: FormatLength,
+    ) -> String {
+        format_time(&self.selected_language.read(), value, length)
+    }
+
+    /// Formats a date-time in the selected language, see [`format_date_time`].
+    pub fn format_date_time<Tz: TimeZone>(
+        &self,
+        value: &chrono::DateTime<Tz>,
+        date_length: FormatLength,
+        time_length: FormatLength,
+    ) -> String {
+        format_date_time(
+            &self.selected_language.read(),
+            value,
+            date_length,
+            time_length,
+        )
+    }
+
+    /// Formats a time relative to now in the selected language, see [`format_relative_time`].
+    pub fn format_relative_time(&self, value: i64, unit: RelativeTimeUnit) -> String {
+        format_relative_time(&self.selected_language.read(), value, unit)
+    }
+
+    /// Joins the items of a list in the selected language, see [`format_list`].
+    pub fn format_list<T: Display>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        style: ListStyle,
+    ) -> String {
+        format_list(&self.selected_language.read(), items, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn german() -> LanguageIdentifier {
+        "de-DE".parse().unwrap()
+    }
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(format_number(&german(), 1234567.5), "1.234.567,5");
+        assert_eq!(format_percent(&german(), 0.25), "25\u{a0}%");
+        assert_eq!(format_currency(&german(), 1234.5, "EUR"), "1.234,50\u{a0}€");
+        assert_eq!(
+            format_currency(&"en-US".parse().unwrap(), 1234.5, "USD"),
+            "$1,234.50"
+        );
+    }
+
+    #[test]
+    fn formats_with_the_patterns_of_the_language() {
+        let language = |id: &str| id.parse::<LanguageIdentifier>().unwrap();
+        assert_eq!(format_percent(&language("tr-TR"), 0.25), "%25");
+        assert_eq!(
+            format_currency(&language("de-CH"), 1234.5, "CHF"),
+            "CHF\u{a0}1’234.50"
+        );
+        assert_eq!(
+            format_currency(&language("ja-JP"), 1234.5, "JPY"),
+            "￥1,234"
+        );
+        assert_eq!(
+            format_currency(&language("en-US"), 1234.5, "KWD"),
+            "KWD\u{a0}1,234.500"
+        );
+    }
+
+    #[test]
+    fn formats_dates() {
+        let value = parse_date_time("2024-03-05T14:07:09+02:00").unwrap();
+        assert_eq!(
+            format_date(&german(), &value, FormatLength::Medium),
+            "05.03.2024"
+        );
+        assert_eq!(format_time(&german(), &value, FormatLength::Short), "14:07");
+        assert_eq!(
+            format_relative_time(&german(), -3, RelativeTimeUnit::Minute),
+            "vor 3 Minuten"
+        );
+        assert_eq!(
+            format_list(&german(), ["a", "b", "c"], ListStyle::And),
+            "a, b und c"
+        );
+    }
+
+    #[test]
+    fn formats_params() {
+        let language = crate::i18n::Language::from_json(
+            r#"{ "id": "de-DE", "texts": { "usage": "{used, number} von {total, number, integer} ({share, number, percent}) am {date, date}" } }"#,
+        )
+        .unwrap();
+        let params = std::collections::HashMap::from([
+            ("used", "1234.5".to_string()),
+            ("total", "2000".to_string()),
+            ("share", "0.62".to_string()),
+            ("date", "2024-03-05T14:07:09+02:00".to_string()),
+        ]);
+        assert_eq!(
+            language.get_text("usage", params).unwrap(),
+            "1.234,5 von 2.000 (62\u{a0}%) am 05.03.2024"
+        );
+    }
+}
@@ -0,0 +1,615 @@
+//! Support for gettext `.po` and `.mo` catalogs.
+
+use std::collections::HashMap;
+use unic_langid::LanguageIdentifier;
+
+use super::plural::{plural_category, PluralCategory, Plurals};
+use super::use_i18n::{Language, LanguageError, Text};
+
+/// A message of a catalog.
+#[derive(Debug, Default)]
+struct Message {
+    context: Option<String>,
+    id: String,
+    plural_id: Option<String>,
+    /// The translation, or one translation per plural form.
+    strings: Vec<String>,
+    /// Fuzzy translations haven't been reviewed and are skipped, like gettext does.
+    fuzzy: bool,
+}
+
+impl Language {
+    /// Creates a language from the source of a gettext `.po` catalog.
+    ///
+    /// Messages are looked up by their `msgid`. Messages with a `msgctxt` are grouped under their context, so they are looked up as `context.msgid`.
+    /// Plural messages select their form from the `count` parameter, using the `Plural-Forms` of the catalog.
+    /// Fuzzy and untranslated messages are skipped.
+    ///
+    /// ```rust
+    /// use dioxus_sdk::i18n::Language;
+    /// use std::collections::HashMap;
+    ///
+    /// let language = Language::from_po(
+    ///     "de-DE".parse().unwrap(),
+    ///     r#"
+    /// msgid ""
+    /// msgstr "Plural-Forms: nplurals=2; plural=(n != 1);\n"
+    ///
+    /// msgctxt "menu"
+    /// msgid "Open"
+    /// msgstr "Öffnen"
+    ///
+    /// msgid "{count} file"
+    /// msgid_plural "{count} files"
+    /// msgstr[0] "{count} Datei"
+    /// msgstr[1] "{count} Dateien"
+    /// "#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(language.get_text("menu.Open", HashMap::new()).unwrap(), "Öffnen");
+    /// let params = HashMap::from([("count", "2".to_string())]);
+    /// assert_eq!(language.get_text("{count} file", params).unwrap(), "2 Dateien");
+    /// ```
+    pub fn from_po(id: LanguageIdentifier, source: &str) -> Result<Self, LanguageError> {
+        let messages = parse_po(source).map_err(LanguageError::Gettext)?;
+        from_messages(id, messages)
+    }
+
+    /// Creates a language from a compiled gettext `.mo` catalog, see [`Language::from_po`].
+    pub fn from_mo(id: LanguageIdentifier, bytes: &[u8]) -> Result<Self, LanguageError> {
+        let messages = parse_mo(bytes).map_err(LanguageError::Gettext)?;
+        from_messages(id, messages)
+    }
+}
+
+fn from_messages(
+    id: LanguageIdentifier,
+    messages: Vec<Message>,
+) -> Result<Language, LanguageError> {
+    // The header is the translation of the empty message
+    let plural_forms = messages
+        .iter()
+        .find(|message| message.id.is_empty() && message.context.is_none())
+        .and_then(|header| header.strings.first())
+        .and_then(|header| {
+            header
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("Plural-Forms:"))
+        })
+        .map(|plural_forms| {
+            let expression = plural_forms
+                .split(';')
+                .find_map(|part| part.trim().strip_prefix("plural="))
+                .ok_or_else(|| format!("no plural expression in {plural_forms:?}"))?;
+            PluralExpression::parse(expression)
+        })
+        .transpose()
+        .map_err(LanguageError::Gettext)?
+        // The plural forms of Germanic languages are the default of gettext
+        .unwrap_or(PluralExpression::Binary(
+            Operator::NotEqual,
+            Box::new(PluralExpression::N),
+            Box::new(PluralExpression::Number(1)),
+        ));
+
+    let mut texts = HashMap::new();
+    for message in messages {
+        let is_translated = message.strings.iter().any(|string| !string.is_empty());
+        if message.id.is_empty() || message.fuzzy || !is_translated {
+            continue;
+        }
+
+        let text = if message.plural_id.is_some() {
+            Text::Plural(plurals(&id, &plural_forms, message.strings))
+        } else {
+            Text::Value(message.strings.into_iter().next().unwrap_or_default())
+        };
+        match message.context {
+            Some(context) => match texts.entry(context).or_insert_with(Text::default) {
+                Text::Texts(group) => {
+                    group.insert(message.id, text);
+                }
+                _ => tracing::warn!(
+                    "The context of \"{}\" is also the ID of a message",
+                    message.id
+                ),
+            },
+            None => {
+                texts.insert(message.id, text);
+            }
+        }
+    }
+
+    Ok(Language {
+        id,
+        texts: Text::Texts(texts),
+        #[cfg(feature = "i18n_fluent")]
+        fluent: None,
+    })
+}
+
+/// Assigns the plural forms of a message to the CLDR plural categories of the language.
+fn plurals(
+    language: &LanguageIdentifier,
+    plural_forms: &PluralExpression,
+    strings: Vec<String>,
+) -> Plurals {
+    // Gettext selects forms by integers, so sample enough of them to hit every category
+    let samples = (0..=200).chain([1000, 10_000, 100_000, 1_000_000]);
+    let mut forms: Vec<(PluralCategory, usize)> = Vec::new();
+    for n in samples {
+        let Some(category) = plural_category(language, &n.to_string()) else {
+            break;
+        };
+        if !forms.iter().any(|(known, _)| *known == category) {
+            forms.push((category, plural_forms.evaluate(n) as usize));
+        }
+    }
+
+    let form = |category: PluralCategory| {
+        let (_, index) = forms.iter().find(|(known, _)| *known == category)?;
+        strings
+            .get(*index)
+            .filter(|string| !string.is_empty())
+            .cloned()
+    };
+    Plurals {
+        zero: form(PluralCategory::Zero),
+        one: form(PluralCategory::One),
+        two: form(PluralCategory::Two),
+        few: form(PluralCategory::Few),
+        many: form(PluralCategory::Many),
+        // Some languages only use `other` for fractions, which gettext selects the last form for
+        other: form(PluralCategory::Other)
+            .or_else(|| strings.last().cloned())
+            .unwrap_or_default(),
+    }
+}
+
+/// The field of a `.po` message that continuation lines are appended to.
+enum Field {
+    Context,
+    Id,
+    PluralId,
+    String(usize),
+}
+
+fn parse_po(source: &str) -> Result<Vec<Message>, String> {
+    let mut messages = Vec::new();
+    let mut message = Message::default();
+    let mut field = None;
+
+    for (number, line) in source.lines().enumerate() {
+        let line = line.trim();
+        let error = |error: &str| format!("line {}: {}", number + 1, error);
+
+        // Obsolete messages are kept as comments
+        if line.is_empty() || line.starts_with("#~") {
+            continue;
+        }
+
+        let (keyword, value) = match line.split_once(char::is_whitespace) {
+            Some((keyword, value)) if !line.starts_with(['"', '#']) => (keyword, value.trim()),
+            _ => ("", line),
+        };
+
+        // Comments and message IDs start a new message once the translations of the previous one have been read
+        let starts_message = line.starts_with('#') || keyword == "msgctxt" || keyword == "msgid";
+        if starts_message && !message.strings.is_empty() {
+            messages.push(std::mem::take(&mut message));
+        }
+
+        if let Some(flags) = line.strip_prefix("#,") {
+            message.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let value = unquote(value).ok_or_else(|| error("expected a quoted string"))?;
+        match keyword {
+            "" => match field {
+                Some(Field::Context) => message
+                    .context
+                    .get_or_insert_with(String::new)
+                    .push_str(&value),
+                Some(Field::Id) => message.id.push_str(&value),
+                Some(Field::PluralId) => message
+                    .plural_id
+                    .get_or_insert_with(String::new)
+                    .push_str(&value),
+                Some(Field::String(index)) => message.strings[index].push_str(&value),
+                None => return Err(error("string without a keyword")),
+            },
+            "msgctxt" => {
+                message.context = Some(value);
+                field = Some(Field::Context);
+            }
+            "msgid" => {
+                message.id = value;
+                field = Some(Field::Id);
+            }
+            "msgid_plural" => {
+                message.plural_id = Some(value);
+                field = Some(Field::PluralId);
+            }
+            keyword => {
+                let index = match keyword.strip_prefix("msgstr") {
+                    Some("") => 0,
+                    Some(index) => index
+                        .strip_prefix('[')
+                        .and_then(|index| index.strip_suffix(']'))
+                        .and_then(|index| index.parse().ok())
+                        .ok_or_else(|| error("invalid plural form index"))?,
+                    None => return Err(error(&format!("unknown keyword {keyword:?}"))),
+                };
+                if message.strings.len() <= index {
+                    message.strings.resize(index + 1, String::new());
+                }
+                message.strings[index] = value;
+                field = Some(Field::String(index));
+            }
+        }
+    }
+    if !message.strings.is_empty() {
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+/// Parses a quoted `.po` string, unescaping it.
+fn unquote(value: &str) -> Option<String> {
+    let value = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unquoted = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => unquoted.push('\n'),
+            't' => unquoted.push('\t'),
+            'r' => unquoted.push('\r'),
+            'a' => unquoted.push('\u{7}'),
+            'b' => unquoted.push('\u{8}'),
+            'f' => unquoted.push('\u{c}'),
+            'v' => unquoted.push('\u{b}'),
+            c => unquoted.push(c),
+        }
+    }
+    Some(unquoted)
+}
+
+fn parse_mo(bytes: &[u8]) -> Result<Vec<Message>, String> {
+    const MAGIC: u32 = 0x950412de;
+
+    let read = |offset: usize, little_endian: bool| -> Result<u32, String> {
+        let word = bytes
+            .get(offset..offset + 4)
+            .ok_or_else(|| format!("unexpected end of file at {offset}"))?;
+        let word = word.try_into().expect("the slice has 4 bytes");
+        Ok(if little_endian {
+            u32::from_le_bytes(word)
+        } else {
+            u32::from_be_bytes(word)
+        })
+    };
+    let little_endian = match read(0, true)? {
+        MAGIC => true,
+        _ if read(0, false)? == MAGIC => false,
+        _ => return Err("not a .mo file".to_string()),
+    };
+    let read = |offset: usize| read(offset, little_endian).map(|word| word as usize);
+    let string = |table: usize, index: usize| -> Result<&str, String> {
+        let length = read(table + index * 8)?;
+        let offset = read(table + index * 8 + 4)?;
+        let string = bytes
+            .get(offset..offset + length)
+            .ok_or_else(|| format!("string {index} is out of bounds"))?;
+        std::str::from_utf8(string).map_err(|err| format!("string {index}: {err}"))
+    };
+
+    let count = read(8)?;
+    let originals = read(12)?;
+    let translations = read(16)?;
+    (0..count)
+        .map(|index| {
+            let original = string(originals, index)?;
+            let (context, original) = match original.split_once('\u{4}') {
+                Some((context, original)) => (Some(context.to_string()), original),
+                None => (None, original),
+            };
+            let (id, plural_id) = match original.split_once('\0') {
+                Some((id, plural_id)) => (id, Some(plural_id.to_string())),
+                None => (original, None),
+            };
+            Ok(Message {
+                context,
+                id: id.to_string(),
+                plural_id,
+                strings: string(translations, index)?
+                    .split('\0')
+                    .map(str::to_string)
+                    .collect(),
+                fuzzy: false,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+impl Operator {
+    /// The operators by precedence, lowest first.
+    const PRECEDENCE: [&'static [(&'static str, Operator)]; 6] = [
+        &[("||", Operator::Or)],
+        &[("&&", Operator::And)],
+        &[("==", Operator::Equal), ("!=", Operator::NotEqual)],
+        &[
+            ("<=", Operator::LessOrEqual),
+            (">=", Operator::GreaterOrEqual),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+        ],
+        &[("+", Operator::Add), ("-", Operator::Subtract)],
+        &[
+            ("*", Operator::Multiply),
+            ("/", Operator::Divide),
+            ("%", Operator::Remainder),
+        ],
+    ];
+
+    fn apply(self, left: u64, right: u64) -> u64 {
+        match self {
+            Operator::Or => (left != 0 || right != 0) as u64,
+            Operator::And => (left != 0 && right != 0) as u64,
+            Operator::Equal => (left == right) as u64,
+            Operator::NotEqual => (left != right) as u64,
+            Operator::Less => (left < right) as u64,
+            Operator::LessOrEqual => (left <= right) as u64,
+            Operator::Greater => (left > right) as u64,
+            Operator::GreaterOrEqual => (left >= right) as u64,
+            Operator::Add => left.wrapping_add(right),
+            Operator::Subtract => left.wrapping_sub(right),
+            Operator::Multiply => left.wrapping_mul(right),
+            Operator::Divide => left.checked_div(right).unwrap_or_default(),
+            Operator::Remainder => left.checked_rem(right).unwrap_or_default(),
+        }
+    }
+}
+
+/// The C expression of the `Plural-Forms` header that selects the plural form for a number `n`.
+#[derive(Debug, Clone, PartialEq)]
+enum PluralExpression {
+    N,
+    Number(u64),
+    Not(Box<PluralExpression>),
+    Binary(Operator, Box<PluralExpression>, Box<PluralExpression>),
+    Conditional(
+        Box<PluralExpression>,
+        Box<PluralExpression>,
+        Box<PluralExpression>,
+    ),
+}
+
+impl PluralExpression {
+    fn parse(source: &str) -> Result<Self, String> {
+        let mut rest = source.trim();
+        let expression = Self::parse_conditional(&mut rest)?;
+        if !rest.is_empty() {
+            return Err(format!(
+                "unexpected {rest:?} in plural expression {source:?}"
+            ));
+        }
+        Ok(expression)
+    }
+
+    fn parse_conditional(rest: &mut &str) -> Result<Self, String> {
+        let condition = Self::parse_binary(rest, 0)?;
+        if !eat(rest, "?") {
+            return Ok(condition);
+        }
+        let then = Self::parse_conditional(rest)?;
+        if !eat(rest, ":") {
+            return Err(format!("expected \":\" at {rest:?}"));
+        }
+        let otherwise = Self::parse_conditional(rest)?;
+        Ok(Self::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn parse_binary(rest: &mut &str, precedence: usize) -> Result<Self, String> {
+        let Some(operators) = Operator::PRECEDENCE.get(precedence) else {
+            return Self::parse_unary(rest);
+        };
+        let mut left = Self::parse_binary(rest, precedence + 1)?;
+        'operators: loop {
+            for (token, operator) in operators.iter() {
+                // `!=` isn't a `!` and `<=` isn't a `<`
+                if eat(rest, token) {
+                    let right = Self::parse_binary(rest, precedence + 1)?;
+                    left = Self::Binary(*operator, Box::new(left), Box::new(right));
+                    continue 'operators;
+                }
+            }
+            return Ok(left);
+        }
+    }
+
+    fn parse_unary(rest: &mut &str) -> Result<Self, String> {
+        if eat(rest, "!") {
+            return Ok(Self::Not(Box::new(Self::parse_unary(rest)?)));
+        }
+        if eat(rest, "(") {
+            let expression = Self::parse_conditional(rest)?;
+            if !eat(rest, ")") {
+                return Err(format!("expected \")\" at {rest:?}"));
+            }
+            return Ok(expression);
+        }
+        if eat(rest, "n") {
+            return Ok(Self::N);
+        }
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let number = rest[..digits]
+            .parse()
+            .map_err(|_| format!("unexpected {rest:?} in plural expression"))?;
+        *rest = rest[digits..].trim_start();
+        Ok(Self::Number(number))
+    }
+
+    fn evaluate(&self, n: u64) -> u64 {
+        match self {
+            Self::N => n,
+            Self::Number(number) => *number,
+            Self::Not(expression) => (expression.evaluate(n) == 0) as u64,
+            Self::Binary(operator, left, right) => {
+                operator.apply(left.evaluate(n), right.evaluate(n))
+            }
+            Self::Conditional(condition, then, otherwise) => {
+                if condition.evaluate(n) != 0 {
+                    then.evaluate(n)
+                } else {
+                    otherwise.evaluate(n)
+                }
+            }
+        }
+    }
+}
+
+/// Consumes a token and the whitespace after it.
+fn eat(rest: &mut &str, token: &str) -> bool {
+    // `!` must not eat the start of `!=`
+    if token == "!" && rest.starts_with("!=") {
+        return false;
+    }
+    match rest.strip_prefix(token) {
+        Some(stripped) => {
+            *rest = stripped.trim_start();
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_plural_forms() {
+        let polish = PluralExpression::parse(
+            "(n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2)",
+        )
+        .unwrap();
+        let forms = [1, 2, 5, 12, 22, 25].map(|n| polish.evaluate(n));
+        assert_eq!(forms, [0, 1, 2, 2, 1, 2]);
+
+        let french = PluralExpression::parse("n>1").unwrap();
+        assert_eq!([0, 1, 2].map(|n| french.evaluate(n)), [0, 0, 1]);
+        assert!(PluralExpression::parse("n >").is_err());
+    }
+
+    #[test]
+    fn assigns_forms_to_categories() {
+        let language = Language::from_po(
+            "pl-PL".parse().unwrap(),
+            r#"
+msgid ""
+msgstr ""
+"Language: pl\n"
+"Plural-Forms: nplurals=3; plural=(n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);\n"
+
+#, fuzzy
+msgid "Untranslated"
+msgstr "Nieprzetłumaczony"
+
+msgid "{count} file"
+msgid_plural "{count} files"
+msgstr[0] "{count} plik"
+msgstr[1] "{count} pliki"
+msgstr[2] "{count} plików"
+"#,
+        )
+        .unwrap();
+
+        let Text::Texts(texts) = &language.texts else {
+            panic!("the texts are a group");
+        };
+        assert!(!texts.contains_key("Untranslated"));
+        let Some(Text::Plural(plurals)) = texts.get("{count} file") else {
+            panic!("the text is a plural");
+        };
+        assert_eq!(plurals.one.as_deref(), Some("{count} plik"));
+        assert_eq!(plurals.few.as_deref(), Some("{count} pliki"));
+        assert_eq!(plurals.many.as_deref(), Some("{count} plików"));
+        assert_eq!(plurals.other, "{count} plików");
+    }
+
+    #[test]
+    fn parses_mo() {
+        // A little-endian catalog with a context message and a plural message
+        let strings: [(&str, &str); 2] = [
+            ("menu\u{4}Open", "Öffnen"),
+            ("file\0files", "Datei\0Dateien"),
+        ];
+        let mut bytes = Vec::new();
+        let header_len = 28 + strings.len() * 16;
+        let mut data = Vec::new();
+        let mut originals = Vec::new();
+        let mut translations = Vec::new();
+        for (original, translation) in strings {
+            originals.push((original.len(), header_len + data.len()));
+            data.extend_from_slice(original.as_bytes());
+            data.push(0);
+            translations.push((translation.len(), header_len + data.len()));
+            data.extend_from_slice(translation.as_bytes());
+            data.push(0);
+        }
+        for word in [
+            0x950412de,
+            0,
+            strings.len(),
+            28,
+            28 + strings.len() * 8,
+            0,
+            0,
+        ] {
+            bytes.extend_from_slice(&(word as u32).to_le_bytes());
+        }
+        for (length, offset) in originals.into_iter().chain(translations) {
+            bytes.extend_from_slice(&(length as u32).to_le_bytes());
+            bytes.extend_from_slice(&(offset as u32).to_le_bytes());
+        }
+        bytes.extend_from_slice(&data);
+
+        let language = Language::from_mo("de-DE".parse().unwrap(), &bytes).unwrap();
+        let params = HashMap::from([("count", "2".to_string())]);
+        assert_eq!(
+            language.get_text("menu.Open", HashMap::new()).unwrap(),
+            "Öffnen"
+        );
+        assert_eq!(language.get_text("file", params).unwrap(), "Dateien");
+    }
+}
@@ -0,0 +1,152 @@
+//! Accessing i18n outside of the components it was initialized for.
+
+use dioxus::prelude::*;
+use std::{cell::RefCell, fmt};
+
+use super::use_i18n::UseI18;
+
+/// Represents errors when accessing i18n.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I18nError {
+    /// i18n wasn't initialized with [`use_init_i18n`](super::use_init_i18n) in an ancestor component, nor anywhere else in the app.
+    NotInitialized,
+}
+
+impl std::error::Error for I18nError {}
+impl fmt::Display for I18nError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            I18nError::NotInitialized => write!(
+                f,
+                "i18n isn't initialized, call `use_init_i18n` in an ancestor component"
+            ),
+        }
+    }
+}
+
+thread_local! {
+    /// The initialized i18n, most recently initialized last.
+    static INITIALIZED: RefCell<Vec<UseI18>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Makes initialized i18n available outside of the component that initialized it, until it's dropped.
+pub(crate) fn register(i18: UseI18) {
+    INITIALIZED.with(|initialized| initialized.borrow_mut().push(i18));
+}
+
+pub(crate) fn unregister(i18: UseI18) {
+    INITIALIZED.with(|initialized| initialized.borrow_mut().retain(|other| *other != i18));
+}
+
+/// Gets i18n without a hook, so it can be used in async tasks, event handlers and code outside of components.
+///
+/// In a component, including its event handlers and tasks, this is the i18n initialized in an ancestor.
+/// Components without one, such as those of a detached window, and code outside of components, e.g. when creating the text of a notification,
+/// get the i18n that was initialized most recently and is still mounted.
+///
+/// ```rust
+/// use dioxus_sdk::i18n::{try_consume_i18, I18nError};
+///
+/// fn notification_title() -> Result<String, I18nError> {
+///     Ok(try_consume_i18()?.translate("notifications.title"))
+/// }
+/// ```
+pub fn try_consume_i18() -> Result<UseI18, I18nError> {
+    if current_scope_id().is_some() {
+        // The i18n of an ancestor takes precedence over the one of another window or root
+        if let Ok(i18) = consume_i18_context() {
+            return Ok(i18);
+        }
+    }
+    INITIALIZED
+        .with(|initialized| initialized.borrow().last().copied())
+        .ok_or(I18nError::NotInitialized)
+}
+
+/// A hook like [`use_i18`](super::use_i18) that returns an error instead of panicking when i18n isn't initialized in an ancestor component.
+///
+/// Unlike [`try_consume_i18`], this never uses the i18n of another window or root.
+pub fn try_use_i18() -> Result<UseI18, I18nError> {
+    use_hook(consume_i18_context)
+}
+
+fn consume_i18_context() -> Result<UseI18, I18nError> {
+    try_consume_context::<UseI18>().ok_or(I18nError::NotInitialized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Found = (&'static str, Result<UseI18, I18nError>);
+
+    thread_local! {
+        static FOUND: RefCell<Vec<Found>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn found(name: &'static str, i18: Result<UseI18, I18nError>) {
+        FOUND.with(|found| found.borrow_mut().push((name, i18)));
+    }
+
+    fn root(name: &'static str) -> Element {
+        let i18 = super::super::use_init_i18n(
+            "en-US".parse().unwrap(),
+            "en-US".parse().unwrap(),
+            Vec::new,
+        );
+        use_hook(|| found(name, Ok(i18)));
+        rsx! {}
+    }
+
+    fn first_root() -> Element {
+        root("first")
+    }
+
+    fn second_root() -> Element {
+        root("second")
+    }
+
+    /// A component of a detached window, which doesn't initialize i18n.
+    fn detached_window() -> Element {
+        let i18 = try_use_i18();
+        use_hook(|| {
+            found("hook", i18);
+            found("consumed", try_consume_i18());
+        });
+        rsx! {}
+    }
+
+    #[test]
+    fn falls_back_to_initialized_i18n() {
+        assert!(matches!(try_consume_i18(), Err(I18nError::NotInitialized)));
+
+        let mut first = VirtualDom::new(first_root);
+        first.rebuild_in_place();
+        let mut second = VirtualDom::new(second_root);
+        second.rebuild_in_place();
+        let mut window = VirtualDom::new(detached_window);
+        window.rebuild_in_place();
+
+        let found = FOUND.take();
+        let [("first", Ok(first_i18)), ("second", Ok(second_i18)), ("hook", hook), ("consumed", consumed)] =
+            found.as_slice()
+        else {
+            panic!("unexpected i18n in the roots");
+        };
+        // The hook only uses the i18n of ancestors, but components without one fall back to the initialized i18n
+        assert!(matches!(hook, Err(I18nError::NotInitialized)));
+        assert!(*consumed == Ok(*second_i18));
+        // Components with an ancestor that initialized i18n use that one
+        let in_first = first.in_runtime(|| ScopeId::ROOT.in_runtime(try_consume_i18));
+        assert!(in_first == Ok(*first_i18));
+
+        // Outside of components, the i18n that was initialized last is used until it's dropped
+        assert!(try_consume_i18() == Ok(*second_i18));
+        drop(second);
+        assert!(try_consume_i18() == Ok(*first_i18));
+        let in_window = window.in_runtime(|| ScopeId::ROOT.in_runtime(try_consume_i18));
+        assert!(in_window == Ok(*first_i18));
+        drop(first);
+        assert!(matches!(try_consume_i18(), Err(I18nError::NotInitialized)));
+    }
+}
@@ -0,0 +1,171 @@
+//! Reloading languages when their files change during development.
+
+use dioxus::prelude::*;
+use futures::{channel::mpsc, StreamExt};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use unic_langid::LanguageIdentifier;
+
+use super::loader::EXTENSIONS;
+use super::use_i18n::{Language, LanguageError};
+use super::use_init_i18n::{I18nConfig, UseInitI18Data};
+
+impl I18nConfig {
+    /// Watches a directory of language files and reloads a language whenever its file changes, without restarting the app.
+    ///
+    /// Files are named like for [`LanguageLoader::directory`](super::LanguageLoader::directory), e.g. `en-US.json` or `en-US.ftl`.
+    /// Languages that are added to the directory while the app runs are added as well.
+    /// This is meant for development, see the example below.
+    ///
+    /// ```rust
+    /// use dioxus_sdk::i18n::I18nConfig;
+    ///
+    /// let config = I18nConfig::new("en-US".parse().unwrap());
+    /// #[cfg(debug_assertions)]
+    /// let config = config.with_hot_reload(concat!(env!("CARGO_MANIFEST_DIR"), "/locales"));
+    /// ```
+    pub fn with_hot_reload(mut self, path: impl Into<PathBuf>) -> Self {
+        self.hot_reload = Some(path.into());
+        self
+    }
+}
+
+/// Reloads the languages in a directory when they change, for as long as the current component is mounted.
+pub(crate) fn watch_languages(data: Signal<UseInitI18Data>, path: PathBuf) {
+    let (sender, mut receiver) = mpsc::unbounded();
+    let watcher = notify::recommended_watcher(move |event| {
+        sender.unbounded_send(event).ok();
+    })
+    .and_then(|mut watcher| {
+        watcher.watch(&path, RecursiveMode::NonRecursive)?;
+        Ok(watcher)
+    });
+    let watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::error!("Failed to watch languages: {}", err);
+            return;
+        }
+    };
+
+    spawn(async move {
+        // Keep watching until the task is dropped
+        let _watcher = watcher;
+        while let Some(event) = receiver.next().await {
+            match event {
+                Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                    for path in event.paths {
+                        reload_language(data, &path);
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => tracing::error!("Failed to watch languages: {}", err),
+            }
+        }
+    });
+}
+
+fn reload_language(mut data: Signal<UseInitI18Data>, path: &Path) {
+    let (Some(id), Some(extension)) = (
+        path.file_stem().and_then(|stem| stem.to_str()),
+        path.extension().and_then(|extension| extension.to_str()),
+    ) else {
+        return;
+    };
+    // Ignore other files, such as the temporary files of editors
+    let Ok(id) = id.parse::<LanguageIdentifier>() else {
+        return;
+    };
+    if !EXTENSIONS.contains(&extension) {
+        return;
+    }
+
+    let language = std::fs::read(path)
+        .map_err(LanguageError::Io)
+        .and_then(|contents| Language::from_file(id, &path.to_string_lossy(), &contents));
+    let language = match language {
+        Ok(language) => language,
+        // The file may be saved again once it is fixed, so keep the previous version until then
+        Err(err) => {
+            tracing::error!("Failed to reload \"{}\": {}", path.display(), err);
+            return;
+        }
+    };
+
+    let mut data = data.write();
+    match data
+        .languages
+        .iter_mut()
+        .find(|existing| existing.id() == language.id())
+    {
+        Some(existing) => *existing = language,
+        None => data.languages.push(language),
+    }
+    // Translations are reported again when the components that use them render with the new language
+    data.missing.borrow_mut().clear();
+    tracing::info!("Reloaded \"{}\"", path.display());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{use_init_i18n_with_config, UseI18};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static I18: RefCell<Option<UseI18>> = const { RefCell::new(None) };
+    }
+
+    fn app() -> Element {
+        let i18 = use_init_i18n_with_config(|| {
+            I18nConfig::new("en-US".parse().unwrap())
+                .with_language(Language::from_json(&language("Hello")).unwrap())
+        });
+        use_hook(|| I18.with(|stored| *stored.borrow_mut() = Some(i18)));
+        rsx! {}
+    }
+
+    fn language(hello: &str) -> String {
+        format!(r#"{{ "id": "en-US", "texts": {{ "hello": "{hello}" }} }}"#)
+    }
+
+    #[test]
+    fn reloads_changed_languages() {
+        let dir =
+            std::env::temp_dir().join(format!("dioxus-sdk-hot-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        let i18 = I18.with(|i18| i18.borrow().unwrap());
+
+        // Reload the files the way the watcher does when they change
+        dom.in_runtime(|| {
+            ScopeId::ROOT.in_runtime(|| {
+                std::fs::write(dir.join("en-US.json"), language("Hi")).unwrap();
+                reload_language(i18.data, &dir.join("en-US.json"));
+                assert_eq!(i18.translate("hello"), "Hi");
+
+                // A file that fails to parse keeps the previous version of the language
+                std::fs::write(dir.join("en-US.json"), "{ \"id\": \"en-US\", ").unwrap();
+                reload_language(i18.data, &dir.join("en-US.json"));
+                assert_eq!(i18.translate("hello"), "Hi");
+
+                // New languages are added, and other files are ignored
+                std::fs::write(dir.join("es-ES.json"), r#"{ "id": "es-ES", "texts": {} }"#)
+                    .unwrap();
+                std::fs::write(dir.join("es-ES.json~"), "").unwrap();
+                reload_language(i18.data, &dir.join("es-ES.json"));
+                reload_language(i18.data, &dir.join("es-ES.json~"));
+                let data = i18.data.read();
+                let ids = data
+                    .languages
+                    .iter()
+                    .map(|language| language.id().to_string());
+                assert_eq!(ids.collect::<Vec<_>>(), ["en-US", "es-ES"]);
+            })
+        });
+
+        drop(dom);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,330 @@
+//! Loading languages on demand.
+
+use dioxus::prelude::*;
+use std::{future::Future, pin::Pin, rc::Rc};
+use unic_langid::LanguageIdentifier;
+
+use super::use_i18n::{Language, LanguageError, UseI18};
+use super::use_init_i18n::I18nConfig;
+
+/// The future returned by a [`LanguageLoader`].
+pub type LanguageFuture = Pin<Box<dyn Future<Output = Result<Language, LanguageError>>>>;
+
+/// Loads the language for an identifier when it is selected but hasn't been loaded yet.
+#[derive(Clone)]
+pub struct LanguageLoader {
+    load: Rc<dyn Fn(LanguageIdentifier) -> LanguageFuture>,
+}
+
+impl LanguageLoader {
+    /// Creates a loader from an async closure.
+    pub fn new<F>(load: impl Fn(LanguageIdentifier) -> F + 'static) -> Self
+    where
+        F: Future<Output = Result<Language, LanguageError>> + 'static,
+    {
+        Self {
+            load: Rc::new(move |id| Box::pin(load(id))),
+        }
+    }
+
+    /// Creates a loader that fetches languages from a URL.
+    ///
+    /// `{id}` in the URL is replaced by the language identifier, e.g. `/locales/{id}.json`.
+    /// URLs ending in `.ftl` are parsed as Fluent resources with the `i18n_fluent` feature, `.po` and `.mo` as gettext catalogs, anything else as JSON.
+    #[cfg(target_family = "wasm")]
+    pub fn url(url: impl Into<String>) -> Self {
+        let url: String = url.into();
+        Self::new(move |id| {
+            let url = url.replace("{id}", &id.to_string());
+            async move {
+                let contents = fetch_bytes(&url).await?;
+                Language::from_file(id, &url, &contents)
+            }
+        })
+    }
+
+    /// Creates a loader that reads languages from a directory.
+    ///
+    /// The language is read from the first file that exists of `{id}.json`, `{id}.ftl` (with the `i18n_fluent` feature), `{id}.po` and `{id}.mo`.
+    /// Files are read on another thread, so the app isn't blocked while reading them.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn directory(path: impl Into<std::path::PathBuf>) -> Self {
+        let path: std::path::PathBuf = path.into();
+        Self::new(move |id| {
+            let files = EXTENSIONS
+                .iter()
+                .map(|extension| path.join(format!("{id}.{extension}")))
+                .collect::<Vec<_>>();
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            std::thread::spawn(move || {
+                let path = files.iter().find(|file| file.exists()).unwrap_or(&files[0]);
+                let language =
+                    std::fs::read(path)
+                        .map_err(LanguageError::Io)
+                        .and_then(|contents| {
+                            Language::from_file(id, &path.to_string_lossy(), &contents)
+                        });
+                sender.send(language).ok();
+            });
+            async move {
+                receiver.await.unwrap_or_else(|_| {
+                    Err(LanguageError::Io(std::io::Error::other(
+                        "the thread reading the language stopped",
+                    )))
+                })
+            }
+        })
+    }
+
+    /// Loads the language for the given identifier.
+    pub fn load(&self, id: LanguageIdentifier) -> LanguageFuture {
+        (self.load)(id)
+    }
+}
+
+/// The extensions of the language files that can be loaded, in the order they are looked for.
+#[cfg(feature = "i18n_fluent")]
+pub(crate) const EXTENSIONS: &[&str] = &["json", "ftl", "po", "mo"];
+#[cfg(not(feature = "i18n_fluent"))]
+pub(crate) const EXTENSIONS: &[&str] = &["json", "po", "mo"];
+
+impl Language {
+    /// Parses a language from the contents of a file, using the file extension of the path to pick the format.
+    pub(crate) fn from_file(
+        id: LanguageIdentifier,
+        path: &str,
+        contents: &[u8],
+    ) -> Result<Self, LanguageError> {
+        if path.ends_with(".mo") {
+            return Language::from_mo(id, contents);
+        }
+        let source = std::str::from_utf8(contents).map_err(|err| {
+            LanguageError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+        })?;
+        #[cfg(feature = "i18n_fluent")]
+        if path.ends_with(".ftl") {
+            return Language::from_ftl(id, source);
+        }
+        if path.ends_with(".po") {
+            Language::from_po(id, source)
+        } else {
+            Language::from_json(source)
+        }
+    }
+}
+
+impl I18nConfig {
+    /// Sets the loader used to load languages that are selected but haven't been added.
+    ///
+    /// While a language is loading, texts are taken from the fallback languages.
+    pub fn with_loader(mut self, loader: LanguageLoader) -> Self {
+        self.loader = Some(loader);
+        self
+    }
+}
+
+impl UseI18 {
+    /// Loads the language with the given identifier if it isn't loaded yet and there is a loader.
+    ///
+    /// A language is only loaded once at a time, even when it's selected again before it has loaded.
+    pub(crate) fn load_language(&self, id: &LanguageIdentifier) {
+        let mut loading = self.loading;
+        let loader = {
+            let data = self.data.peek();
+            // Pseudo-locales are generated from the fallback language, so there is nothing to load
+            let is_loaded = data.languages.iter().any(|language| language.id() == id)
+                || data.pseudo_locale(id).is_some();
+            let loader = match &data.loader {
+                Some(loader) if !is_loaded => loader.clone(),
+                _ => return,
+            };
+            let mut pending_loads = data.pending_loads.borrow_mut();
+            if pending_loads.contains(id) {
+                // It's loading again, but its texts are still taken from the fallback languages until then
+                if loading.peek().as_ref() != Some(id) {
+                    loading.set(Some(id.clone()));
+                }
+                return;
+            }
+            pending_loads.push(id.clone());
+            loader
+        };
+
+        let mut data = self.data;
+        let id = id.clone();
+        loading.set(Some(id.clone()));
+        // The task shouldn't be cancelled when the component that selected the language goes away.
+        spawn_forever(async move {
+            let language = loader.load(id.clone()).await.and_then(|language| {
+                // Otherwise the requested language would still be missing and be loaded again every time it's selected
+                if language.id() == &id {
+                    Ok(language)
+                } else {
+                    Err(LanguageError::WrongId {
+                        requested: id.clone(),
+                        loaded: language.id().clone(),
+                    })
+                }
+            });
+            let mut data = data.write();
+            data.pending_loads
+                .borrow_mut()
+                .retain(|pending| *pending != id);
+            match language {
+                // The language may have been added while it was loading, e.g. by hot reloading
+                Ok(language) => match data
+                    .languages
+                    .iter_mut()
+                    .find(|existing| existing.id() == language.id())
+                {
+                    Some(existing) => *existing = language,
+                    None => data.languages.push(language),
+                },
+                Err(err) => tracing::error!("Failed to load language \"{}\": {}", id, err),
+            }
+            drop(data);
+            if loading.peek().as_ref() == Some(&id) {
+                loading.set(None);
+            }
+        });
+    }
+
+    /// Gets whether a language is currently being loaded.
+    pub fn is_loading(&self) -> bool {
+        self.loading.read().is_some()
+    }
+
+    /// Gets the language that is currently being loaded, if any.
+    pub fn loading_language(&self) -> Option<LanguageIdentifier> {
+        self.loading.read().clone()
+    }
+}
+
+#[cfg(target_family = "wasm")]
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>, LanguageError> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let fetch_error = |err: wasm_bindgen::JsValue| LanguageError::Fetch(format!("{err:?}"));
+
+    let window =
+        web_sys::window().ok_or_else(|| LanguageError::Fetch("no window found".to_string()))?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .map_err(fetch_error)?
+        .dyn_into()
+        .map_err(fetch_error)?;
+    if !response.ok() {
+        return Err(LanguageError::Fetch(format!(
+            "{} returned status {}",
+            url,
+            response.status()
+        )));
+    }
+    let buffer = JsFuture::from(response.array_buffer().map_err(fetch_error)?)
+        .await
+        .map_err(fetch_error)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::use_init_i18n_with_config;
+    use std::cell::{Cell, RefCell};
+
+    thread_local! {
+        static LOADS: Cell<usize> = const { Cell::new(0) };
+        static I18: RefCell<Option<UseI18>> = const { RefCell::new(None) };
+    }
+
+    fn app() -> Element {
+        let i18 = use_init_i18n_with_config(|| {
+            I18nConfig::new("en-US".parse().unwrap()).with_loader(LanguageLoader::new(|id| {
+                LOADS.set(LOADS.get() + 1);
+                // A file that has the language of another region
+                let id = match id.to_string().as_str() {
+                    "pt-BR" => "pt-PT".to_string(),
+                    id => id.to_string(),
+                };
+                async move { Language::from_json(&format!(r#"{{ "id": "{id}", "texts": {{}} }}"#)) }
+            }))
+        });
+        use_hook(|| I18.with(|stored| *stored.borrow_mut() = Some(i18)));
+        rsx! {}
+    }
+
+    #[test]
+    fn loads_languages_once() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        dom.process_events();
+        let mut i18 = I18.with(|i18| i18.borrow().unwrap());
+
+        // Select a language again while it's still loading, from the app like a component would
+        dom.in_runtime(|| {
+            ScopeId::ROOT.in_runtime(|| {
+                for id in ["es-ES", "fr-FR", "es-ES"] {
+                    i18.set_language(id.parse().unwrap());
+                }
+                assert_eq!(*i18.loading.peek(), Some("es-ES".parse().unwrap()));
+            })
+        });
+        dom.process_events();
+
+        dom.in_runtime(|| {
+            ScopeId::ROOT.in_runtime(|| {
+                let data = i18.data.peek();
+                let ids = data
+                    .languages
+                    .iter()
+                    .map(|language| language.id().to_string());
+                assert_eq!(ids.collect::<Vec<_>>(), ["en-US", "es-ES", "fr-FR"]);
+                assert!(!i18.is_loading());
+            })
+        });
+        assert_eq!(LOADS.get(), 3);
+    }
+
+    #[test]
+    fn rejects_languages_with_another_id() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        let mut i18 = I18.with(|i18| i18.borrow().unwrap());
+
+        dom.in_runtime(|| ScopeId::ROOT.in_runtime(|| i18.set_language("pt-BR".parse().unwrap())));
+        dom.process_events();
+
+        dom.in_runtime(|| {
+            ScopeId::ROOT.in_runtime(|| {
+                let data = i18.data.peek();
+                let ids = data
+                    .languages
+                    .iter()
+                    .map(|language| language.id().to_string());
+                assert_eq!(ids.collect::<Vec<_>>(), ["en-US"]);
+                assert!(data.pending_loads.borrow().is_empty());
+                assert_eq!(i18.loading_language(), None);
+            })
+        });
+    }
+
+    #[test]
+    fn reads_languages_from_a_directory() {
+        let dir = std::env::temp_dir().join(format!("dioxus-sdk-loader-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("es-ES.po"), "msgid \"hello\"\nmsgstr \"Hola\"\n").unwrap();
+
+        let loader = LanguageLoader::directory(&dir);
+        let language = futures::executor::block_on(loader.load("es-ES".parse().unwrap())).unwrap();
+        assert_eq!(
+            language.get_text("hello", Default::default()).unwrap(),
+            "Hola"
+        );
+        assert!(matches!(
+            futures::executor::block_on(loader.load("fr-FR".parse().unwrap())),
+            Err(LanguageError::Io(_))
+        ));
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
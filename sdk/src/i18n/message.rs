@@ -0,0 +1,430 @@
+//! Texts in the [ICU MessageFormat](https://unicode-org.github.io/icu/userguide/format_parse/messages/) syntax.
+
+use std::collections::HashMap;
+
+use super::direction::{bidi_isolate, text_direction, TextDirection};
+use super::plural::{category_name, ordinal_category, plural_category};
+use super::pseudo::PseudoLocale;
+use super::use_i18n::Language;
+
+/// A part of a message.
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Text(String),
+    /// A `{name}` or `{name, type, style}` argument.
+    Argument {
+        name: &'a str,
+        /// How the value is formatted, e.g. `number, percent`.
+        format: Option<&'a str>,
+    },
+    /// A `{name, select|plural|selectordinal, ...}` argument that selects one of its cases.
+    Select {
+        name: &'a str,
+        kind: SelectKind,
+        /// The number subtracted from the value before its plural category is selected.
+        offset: f64,
+        cases: Vec<(&'a str, Vec<Part<'a>>)>,
+    },
+    /// `#` in a plural case, the number minus the offset.
+    Number,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SelectKind {
+    /// Selects the case named like the value, e.g. `female`.
+    Select,
+    /// Selects the case for the value, e.g. `=0`, or for its cardinal plural category, e.g. `one`.
+    Plural,
+    /// Selects the case for the value, e.g. `=0`, or for its ordinal plural category, e.g. `two` for `2nd`.
+    Ordinal,
+}
+
+/// Parses a message.
+///
+/// Arguments that aren't valid are kept as text, so texts with braces that aren't arguments still work.
+fn parse_message(text: &str) -> Vec<Part<'_>> {
+    Parser { text, pos: 0 }.message(false, false)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let eaten = self.rest().starts_with(s);
+        if eaten {
+            self.pos += s.len();
+        }
+        eaten
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Parses a name, type or selector.
+    fn word(&mut self) -> &'a str {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, ',' | '{' | '}'))
+            .unwrap_or(rest.len());
+        self.pos += end;
+        &rest[..end]
+    }
+
+    /// Parses a message until the end of the text or, if it's nested, the `}` that closes it.
+    fn message(&mut self, in_plural: bool, nested: bool) -> Vec<Part<'a>> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        while let Some(c) = self.rest().chars().next() {
+            match c {
+                '}' if nested => break,
+                '{' => {
+                    let start = self.pos;
+                    if let Some(argument) = self.argument(in_plural) {
+                        if !text.is_empty() {
+                            parts.push(Part::Text(std::mem::take(&mut text)));
+                        }
+                        parts.push(argument);
+                    } else {
+                        self.pos = start + 1;
+                        text.push('{');
+                    }
+                }
+                '#' if in_plural => {
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Number);
+                    self.pos += 1;
+                }
+                '\'' => self.quoted(in_plural, &mut text),
+                _ => {
+                    text.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        parts
+    }
+
+    /// Parses an apostrophe, which escapes a special character or is an apostrophe itself.
+    ///
+    /// `''` is an apostrophe and `'{text}'` quotes the text until the next apostrophe, any other apostrophe is kept.
+    fn quoted(&mut self, in_plural: bool, text: &mut String) {
+        self.pos += 1;
+        if self.eat("'") {
+            text.push('\'');
+            return;
+        }
+        let rest = self.rest();
+        let is_quote = rest.starts_with(['{', '}', '|']) || (in_plural && rest.starts_with('#'));
+        if !is_quote {
+            text.push('\'');
+            return;
+        }
+        loop {
+            let rest = self.rest();
+            let Some(end) = rest.find('\'') else {
+                // The quote isn't closed, so the rest of the text is quoted
+                text.push_str(rest);
+                self.pos = self.text.len();
+                return;
+            };
+            text.push_str(&rest[..end]);
+            self.pos += end + 1;
+            if !self.eat("'") {
+                return;
+            }
+            text.push('\'');
+        }
+    }
+
+    fn argument(&mut self, in_plural: bool) -> Option<Part<'a>> {
+        self.pos += 1;
+        self.skip_whitespace();
+        let name = self.word();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        self.skip_whitespace();
+        if self.eat("}") {
+            return Some(Part::Argument { name, format: None });
+        }
+        if !self.eat(",") {
+            return None;
+        }
+
+        self.skip_whitespace();
+        let format_start = self.pos;
+        let kind = match self.word() {
+            "select" => SelectKind::Select,
+            "plural" => SelectKind::Plural,
+            "selectordinal" => SelectKind::Ordinal,
+            _ => {
+                let end = self.rest().find(['{', '}'])?;
+                if !self.rest()[end..].starts_with('}') {
+                    return None;
+                }
+                let format = self.text[format_start..self.pos + end].trim();
+                self.pos += end + 1;
+                return Some(Part::Argument {
+                    name,
+                    format: Some(format),
+                });
+            }
+        };
+        self.skip_whitespace();
+        if !self.eat(",") {
+            return None;
+        }
+
+        self.skip_whitespace();
+        let mut offset = 0.0;
+        if kind != SelectKind::Select && self.eat("offset:") {
+            self.skip_whitespace();
+            offset = self.word().parse().ok()?;
+        }
+        let mut cases = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat("}") {
+                break;
+            }
+            let selector = self.word();
+            self.skip_whitespace();
+            if selector.is_empty() || !self.eat("{") {
+                return None;
+            }
+            let message = self.message(in_plural || kind != SelectKind::Select, true);
+            if !self.eat("}") {
+                return None;
+            }
+            cases.push((selector, message));
+        }
+        (!cases.is_empty()).then_some(Part::Select {
+            name,
+            kind,
+            offset,
+            cases,
+        })
+    }
+}
+
+impl Language {
+    /// Formats a message with the parameters, adding the parameters it uses that aren't in `params` to `missing_params`.
+    ///
+    /// Arguments whose parameter is missing are kept as they are.
+    /// With a pseudo-locale the text of the message is transformed, but not the values of its parameters.
+    pub(crate) fn format_message(
+        &self,
+        text: &str,
+        params: &HashMap<&str, String>,
+        pseudo_locale: Option<PseudoLocale>,
+        missing_params: &mut Vec<String>,
+    ) -> String {
+        let mut formatter = Formatter {
+            language: self,
+            params,
+            pseudo_locale,
+            // Keep parameters written in another direction from scrambling the text around them
+            is_rtl: pseudo_locale
+                .map_or_else(|| text_direction(&self.id), |pseudo| pseudo.direction())
+                == TextDirection::Rtl,
+            missing_params,
+            result: String::with_capacity(text.len()),
+        };
+        formatter.format(&parse_message(text), None);
+        formatter.result
+    }
+}
+
+struct Formatter<'a> {
+    language: &'a Language,
+    params: &'a HashMap<&'a str, String>,
+    pseudo_locale: Option<PseudoLocale>,
+    is_rtl: bool,
+    missing_params: &'a mut Vec<String>,
+    result: String,
+}
+
+impl<'a> Formatter<'a> {
+    fn param(&mut self, name: &str) -> Option<&'a str> {
+        let params = self.params;
+        let value = params.get(name).map(String::as_str);
+        if value.is_none() && !self.missing_params.iter().any(|param| param == name) {
+            self.missing_params.push(name.to_string());
+        }
+        value
+    }
+
+    fn push_value(&mut self, value: &str) {
+        if self.is_rtl {
+            self.result.push_str(&bidi_isolate(value));
+        } else {
+            self.result.push_str(value);
+        }
+    }
+
+    /// Formats the parts of a message, where `number` is the value of `#` in plural cases.
+    fn format(&mut self, parts: &[Part], number: Option<&str>) {
+        for part in parts {
+            match part {
+                Part::Text(text) => match self.pseudo_locale {
+                    Some(pseudo_locale) => self.result.push_str(&pseudo_locale.transform(text)),
+                    None => self.result.push_str(text),
+                },
+                Part::Argument { name, format } => match self.param(name) {
+                    Some(value) => {
+                        let value = self.language.format_param(value, *format);
+                        self.push_value(&value);
+                    }
+                    None => match format {
+                        Some(format) => self.result.push_str(&format!("{{{name}, {format}}}")),
+                        None => self.result.push_str(&format!("{{{name}}}")),
+                    },
+                },
+                Part::Number => match number {
+                    Some(number) => {
+                        let number = self.language.format_param(number, Some("number"));
+                        self.push_value(&number);
+                    }
+                    None => self.result.push('#'),
+                },
+                Part::Select {
+                    name,
+                    kind,
+                    offset,
+                    cases,
+                } => {
+                    let value = self.param(name);
+                    let number = match kind {
+                        SelectKind::Select => number.map(str::to_string),
+                        SelectKind::Plural | SelectKind::Ordinal => value.and_then(|value| {
+                            if *offset == 0.0 {
+                                return Some(value.trim().to_string());
+                            }
+                            let number = value.trim().parse::<f64>().ok()?;
+                            Some((number - offset).to_string())
+                        }),
+                    };
+                    let case =
+                        select_case(&self.language.id, *kind, cases, value, number.as_deref());
+                    if let Some(case) = case {
+                        self.format(case, number.as_deref());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Selects the case for a value, or `other` if there is no case for it.
+fn select_case<'c, 'a>(
+    language: &unic_langid::LanguageIdentifier,
+    kind: SelectKind,
+    cases: &'c [(&'a str, Vec<Part<'a>>)],
+    value: Option<&str>,
+    number: Option<&str>,
+) -> Option<&'c [Part<'a>]> {
+    let find = |selector: &str| {
+        cases
+            .iter()
+            .find(|(case, _)| *case == selector)
+            .map(|(_, parts)| parts.as_slice())
+    };
+    let selected = match kind {
+        SelectKind::Select => value.and_then(find),
+        SelectKind::Plural | SelectKind::Ordinal => {
+            // Exact matches like `=0` are compared with the value before the offset is subtracted
+            let value = value.and_then(|value| value.trim().parse::<f64>().ok());
+            let exact = cases.iter().find(|(case, _)| {
+                let exact = case.strip_prefix('=').and_then(|n| n.parse::<f64>().ok());
+                exact.is_some() && exact == value
+            });
+            let category = number.and_then(|number| match kind {
+                SelectKind::Ordinal => ordinal_category(language, number),
+                _ => plural_category(language, number),
+            });
+            exact
+                .map(|(_, parts)| parts.as_slice())
+                .or_else(|| find(category_name(category?)))
+        }
+    };
+    selected.or_else(|| find("other"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(text: &str, params: &[(&str, &str)]) -> String {
+        let language = Language::from_json(r#"{ "id": "en-US", "texts": {} }"#).unwrap();
+        let params = params
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .collect();
+        language.format_message(text, &params, None, &mut Vec::new())
+    }
+
+    #[test]
+    fn selects_cases() {
+        let text =
+            "{gender, select, female {She} male {He} other {They}} replied to {name}, {name}!";
+        assert_eq!(
+            format(text, &[("gender", "female"), ("name", "Ann")]),
+            "She replied to Ann, Ann!"
+        );
+        assert_eq!(
+            format(text, &[("gender", "robot"), ("name", "Ann")]),
+            "They replied to Ann, Ann!"
+        );
+
+        let text = "{count, plural, offset:1 =0 {Nobody} =1 {{host}} one {{host} and # other} other {{host} and # others}}";
+        assert_eq!(format(text, &[("count", "0"), ("host", "Ann")]), "Nobody");
+        assert_eq!(format(text, &[("count", "1"), ("host", "Ann")]), "Ann");
+        assert_eq!(
+            format(text, &[("count", "2"), ("host", "Ann")]),
+            "Ann and 1 other"
+        );
+        assert_eq!(
+            format(text, &[("count", "5"), ("host", "Ann")]),
+            "Ann and 4 others"
+        );
+
+        let text = "{place, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}";
+        assert_eq!(format(text, &[("place", "22")]), "22nd");
+    }
+
+    #[test]
+    fn escapes_and_keeps_invalid_arguments() {
+        assert_eq!(
+            format("It''s '{name}' and {name}'s", &[("name", "Ann")]),
+            "It's {name} and Ann's"
+        );
+        assert_eq!(
+            format("{count, plural, other {'#' is #}}", &[("count", "3")]),
+            "# is 3"
+        );
+        assert_eq!(
+            format("{ not an argument } {", &[]),
+            "{ not an argument } {"
+        );
+
+        let language = Language::from_json(r#"{ "id": "en-US", "texts": {} }"#).unwrap();
+        let mut missing_params = Vec::new();
+        let text = language.format_message("Hi {name}", &HashMap::new(), None, &mut missing_params);
+        assert_eq!(text, "Hi {name}");
+        assert_eq!(missing_params, ["name"]);
+    }
+}
@@ -0,0 +1,237 @@
+//! Reporting translations that are missing.
+
+use dioxus::prelude::*;
+use std::{collections::BTreeSet, fmt};
+use unic_langid::LanguageIdentifier;
+
+use super::use_i18n::{Language, Text, UseI18};
+use super::use_init_i18n::{I18nConfig, UseInitI18Data};
+
+/// What to render when a key isn't translated in the selected language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingPolicy {
+    /// Use the text of the fallback languages, or the key if none of them has it.
+    #[default]
+    Fallback,
+    /// Render the key.
+    Key,
+    /// Render the key behind a marker (`⚠ key`), so missing texts stand out while testing.
+    Marker,
+}
+
+impl MissingPolicy {
+    pub(crate) fn marker(key: &str) -> String {
+        format!("⚠ {key}")
+    }
+}
+
+/// A translation that was missing while translating.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingTranslation {
+    /// The key isn't translated in the language.
+    Key {
+        language: LanguageIdentifier,
+        key: String,
+    },
+    /// The text uses a parameter that wasn't passed.
+    Param {
+        language: LanguageIdentifier,
+        key: String,
+        param: String,
+    },
+}
+
+impl fmt::Display for MissingTranslation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MissingTranslation::Key { language, key } => {
+                write!(f, "\"{}\" is not translated in {}", key, language)
+            }
+            MissingTranslation::Param {
+                language,
+                key,
+                param,
+            } => write!(
+                f,
+                "\"{}\" in {} is missing the parameter \"{}\"",
+                key, language, param
+            ),
+        }
+    }
+}
+
+impl UseInitI18Data {
+    /// Adds a missing translation to the report, unless it has been reported before.
+    pub(crate) fn report_missing(&self, missing: MissingTranslation) {
+        let mut report = self.missing.borrow_mut();
+        if !report.contains(&missing) {
+            tracing::warn!("{}", missing);
+            report.push(missing);
+        }
+    }
+}
+
+impl I18nConfig {
+    /// Sets what to render when a key isn't translated in the selected language.
+    pub fn with_missing_policy(mut self, missing_policy: MissingPolicy) -> Self {
+        self.missing_policy = missing_policy;
+        self
+    }
+}
+
+impl UseI18 {
+    /// Gets the translations that were missing since the start or since they were last cleared, in the order they were found.
+    ///
+    /// Translations are only found to be missing when they are rendered.
+    pub fn missing_translations(&self) -> Vec<MissingTranslation> {
+        self.data.peek().missing.borrow().clone()
+    }
+
+    /// Clears the report of missing translations.
+    pub fn clear_missing_translations(&self) {
+        self.data.peek().missing.borrow_mut().clear();
+    }
+
+    /// Sets what to render when a key isn't translated in the selected language.
+    pub fn set_missing_policy(&mut self, missing_policy: MissingPolicy) {
+        self.data.write().missing_policy = missing_policy;
+    }
+}
+
+/// The difference between the keys of a language and those of a reference language, see [`Language::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageDiff {
+    /// Keys of the reference language that the language doesn't translate.
+    pub untranslated: Vec<String>,
+    /// Keys of the language that the reference language doesn't have.
+    pub obsolete: Vec<String>,
+}
+
+impl LanguageDiff {
+    /// Whether the language has exactly the keys of the reference language.
+    pub fn is_empty(&self) -> bool {
+        self.untranslated.is_empty() && self.obsolete.is_empty()
+    }
+}
+
+impl Language {
+    /// Gets the paths of all texts in this language, sorted.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys = BTreeSet::new();
+        text_keys(&self.texts, String::new(), &mut keys);
+        #[cfg(feature = "i18n_fluent")]
+        if let Some(fluent) = &self.fluent {
+            keys.extend(fluent.keys().iter().cloned());
+        }
+        keys.into_iter().collect()
+    }
+
+    /// Compares the keys of this language with those of a reference language, usually the one the app is written in.
+    ///
+    /// ```rust
+    /// use dioxus_sdk::i18n::Language;
+    ///
+    /// let reference = Language::from_json(
+    ///     r#"{ "id": "en-US", "texts": { "hello": "Hello", "bye": "Bye" } }"#,
+    /// )
+    /// .unwrap();
+    /// let language = Language::from_json(
+    ///     r#"{ "id": "es-ES", "texts": { "hello": "Hola", "welcome": "Bienvenido" } }"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// let diff = language.diff(&reference);
+    /// assert_eq!(diff.untranslated, ["bye"]);
+    /// assert_eq!(diff.obsolete, ["welcome"]);
+    /// ```
+    pub fn diff(&self, reference: &Language) -> LanguageDiff {
+        let keys = self.keys();
+        let reference_keys = reference.keys();
+        LanguageDiff {
+            untranslated: reference_keys
+                .iter()
+                .filter(|key| !keys.contains(key))
+                .cloned()
+                .collect(),
+            obsolete: keys
+                .iter()
+                .filter(|key| !reference_keys.contains(key))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+fn text_keys(text: &Text, path: String, keys: &mut BTreeSet<String>) {
+    match text {
+        Text::Texts(texts) => {
+            for (key, text) in texts {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                text_keys(text, path, keys);
+            }
+        }
+        Text::Value(_) | Text::Plural(_) => {
+            keys.insert(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn finds_missing_params() {
+        let json = Language::from_json(
+            r#"{ "id": "en-US", "texts": { "hello": "Hello {name}, you have {count} messages" } }"#,
+        )
+        .unwrap();
+
+        let mut missing = Vec::new();
+        let params = HashMap::from([("count", "2".to_string())]);
+        let text = json
+            .format_text("hello", params, None, &mut missing)
+            .unwrap();
+        assert_eq!(text, "Hello {name}, you have 2 messages");
+        assert_eq!(missing, ["name"]);
+    }
+
+    #[cfg(feature = "i18n_fluent")]
+    #[test]
+    fn finds_missing_fluent_params() {
+        let ftl = Language::from_ftl("en-US".parse().unwrap(), "hello = Hello { $name }").unwrap();
+
+        let mut missing = Vec::new();
+        ftl.format_text("hello", HashMap::new(), None, &mut missing)
+            .unwrap();
+        assert_eq!(missing, ["name"]);
+    }
+
+    #[cfg(feature = "i18n_fluent")]
+    #[test]
+    fn diffs_fluent_keys() {
+        let reference = Language::from_ftl(
+            "en-US".parse().unwrap(),
+            "hello = Hello\nlogin = Log in\n    .title = Log in to your account",
+        )
+        .unwrap();
+        let language = Language::from_json(
+            r#"{ "id": "es-ES", "texts": { "hello": "Hola", "login": { "title": "Inicia sesión" } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(reference.keys(), ["hello", "login", "login.title"]);
+        assert_eq!(
+            language.diff(&reference),
+            LanguageDiff {
+                untranslated: vec!["login".to_string()],
+                obsolete: Vec::new(),
+            }
+        );
+    }
+}
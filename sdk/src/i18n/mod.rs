@@ -1,6 +1,58 @@
+mod direction;
+mod gettext;
+mod global;
+mod loader;
+mod message;
+mod missing;
+mod negotiate;
+mod plural;
+mod pseudo;
+mod rich_text;
+mod scope;
 mod tanslate;
 mod use_i18n;
 mod use_init_i18n;
+mod use_system_locale;
 
+pub use self::direction::*;
+pub use self::global::*;
+pub use self::loader::*;
+pub use self::missing::*;
+pub use self::negotiate::*;
+pub use self::plural::*;
+pub use self::pseudo::*;
+pub use self::scope::*;
 pub use self::use_i18n::*;
 pub use self::use_init_i18n::*;
+pub use self::use_system_locale::*;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "i18n_fluent")] {
+        mod fluent;
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "i18n_macro")] {
+        pub use dioxus_sdk_macro::translations;
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "i18n_format")] {
+        mod format;
+        pub use self::format::*;
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "storage")] {
+        mod persist;
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(all(feature = "i18n_hot_reload", not(target_family = "wasm")))] {
+        mod hot_reload;
+    }
+}
@@ -0,0 +1,140 @@
+//! Locale negotiation between requested and available languages.
+
+use unic_langid::LanguageIdentifier;
+
+/// How closely an available language matches a requested one. Lower is better.
+fn match_level(requested: &LanguageIdentifier, available: &LanguageIdentifier) -> Option<u8> {
+    if requested == available {
+        return Some(0);
+    }
+    if requested.language != available.language {
+        return None;
+    }
+    let is_generic = available.region.is_none() && available.variants().next().is_none();
+    if requested.script == available.script {
+        // Prefer the generic language and script (`zh-Hant`) over siblings in other regions (`zh-Hant-HK`)
+        return Some(if is_generic { 1 } else { 2 });
+    }
+    // Prefer the bare language (`en`) over siblings with other scripts or regions
+    Some(if is_generic && available.script.is_none() {
+        3
+    } else {
+        4
+    })
+}
+
+/// Sorts the available languages that match the requested one from best to worst match.
+fn matches<'a>(
+    requested: &LanguageIdentifier,
+    available: impl IntoIterator<Item = &'a LanguageIdentifier>,
+) -> Vec<&'a LanguageIdentifier> {
+    let mut matches = available
+        .into_iter()
+        .filter_map(|available| Some((match_level(requested, available)?, available)))
+        .collect::<Vec<_>>();
+    // The sort is stable so languages that match equally well keep their order
+    matches.sort_by_key(|(level, _)| *level);
+    matches
+        .into_iter()
+        .map(|(_, available)| available)
+        .collect()
+}
+
+/// Picks the available language that best matches the requested languages.
+///
+/// The requested languages are tried in order of preference. For each one an exact match is preferred,
+/// then a language with the same language and script (`zh-Hant-TW` → `zh-Hant`), and then a language with the same language (`en-GB` → `en` or `en-US`).
+///
+/// ```rust
+/// use dioxus_sdk::i18n::negotiate_language;
+/// use unic_langid::LanguageIdentifier;
+///
+/// let requested: Vec<LanguageIdentifier> = vec!["fr-CA".parse().unwrap(), "en-GB".parse().unwrap()];
+/// let available: Vec<LanguageIdentifier> = vec!["en".parse().unwrap(), "es-ES".parse().unwrap()];
+/// assert_eq!(negotiate_language(&requested, &available), Some(&available[0]));
+/// ```
+pub fn negotiate_language<'a>(
+    requested: &[LanguageIdentifier],
+    available: &'a [LanguageIdentifier],
+) -> Option<&'a LanguageIdentifier> {
+    requested
+        .iter()
+        .find_map(|requested| matches(requested, available).into_iter().next())
+}
+
+/// Gets the languages that keys should be looked up in, in order of preference.
+///
+/// These are the languages that match the selected language, followed by the languages that match each of the fallback languages.
+/// Languages that match equally well keep their order.
+pub(crate) fn fallback_chain<'a, T>(
+    selected: &LanguageIdentifier,
+    fallbacks: &[LanguageIdentifier],
+    languages: &'a [T],
+    id: impl Fn(&T) -> &LanguageIdentifier,
+) -> Vec<&'a T> {
+    let mut chain: Vec<&T> = Vec::new();
+    for requested in std::iter::once(selected).chain(fallbacks) {
+        let mut matches = languages
+            .iter()
+            .filter_map(|language| Some((match_level(requested, id(language))?, language)))
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(level, _)| *level);
+        for (_, language) in matches {
+            if !chain.iter().any(|chained| std::ptr::eq(*chained, language)) {
+                chain.push(language);
+            }
+        }
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    fn ids(ids: &[&str]) -> Vec<LanguageIdentifier> {
+        ids.iter().map(|id| id.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn negotiates_closest_language() {
+        let available = ids(&["en-US", "en", "zh-Hant", "zh-Hans-CN"]);
+        let negotiate = |requested: &[&str]| negotiate_language(&ids(requested), &available);
+
+        assert_eq!(negotiate(&["en-US"]), Some(&available[0]));
+        assert_eq!(negotiate(&["en-GB"]), Some(&available[1]));
+        assert_eq!(negotiate(&["zh-Hant-TW"]), Some(&available[2]));
+        assert_eq!(negotiate(&["zh-Hans"]), Some(&available[3]));
+        assert_eq!(negotiate(&["fr-FR", "en-AU"]), Some(&available[1]));
+        assert_eq!(negotiate(&["fr-FR"]), None);
+    }
+
+    #[test]
+    fn falls_through_per_key() {
+        let languages = [
+            r#"{ "id": "en-US", "texts": { "hello": "Hello", "color": "Color" } }"#,
+            r#"{ "id": "en", "texts": { "hello": "Hi", "color": "Colour", "bye": "Bye" } }"#,
+            r#"{ "id": "es-ES", "texts": { "hello": "Hola", "only_spanish": "Solo" } }"#,
+        ]
+        .map(|language| Language::from_str(language).unwrap());
+        let translate = |selected: &str, key: &str| {
+            fallback_chain(
+                &selected.parse().unwrap(),
+                &ids(&["es-ES"]),
+                &languages,
+                Language::id,
+            )
+            .into_iter()
+            .find_map(|language| language.get_text(key, HashMap::default()))
+        };
+
+        assert_eq!(translate("en-US", "color").as_deref(), Some("Color"));
+        assert_eq!(translate("en-GB", "color").as_deref(), Some("Colour"));
+        assert_eq!(translate("en-US", "bye").as_deref(), Some("Bye"));
+        assert_eq!(translate("en-GB", "only_spanish").as_deref(), Some("Solo"));
+        assert_eq!(translate("en-GB", "missing"), None);
+    }
+}
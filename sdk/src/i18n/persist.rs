@@ -0,0 +1,82 @@
+//! Persisting the selected language with the storage module.
+
+use unic_langid::LanguageIdentifier;
+
+use super::use_init_i18n::I18nConfig;
+use crate::storage::{new_synced_storage, StorageBacking, StorageSubscriber};
+
+impl I18nConfig {
+    /// Stores the selected language under the given key, so the user's choice is restored the next time the app starts.
+    ///
+    /// A stored language takes precedence over the one selected by the configuration, including a [negotiated](I18nConfig::negotiate) one.
+    /// When the language is changed in another window or tab, the change is applied to this one as well.
+    ///
+    /// ```rust
+    /// use dioxus::prelude::*;
+    /// use dioxus_sdk::i18n::{use_init_i18n_with_config, I18nConfig};
+    /// use dioxus_sdk::storage::LocalStorage;
+    ///
+    /// fn App() -> Element {
+    ///     use_init_i18n_with_config(|| {
+    ///         I18nConfig::new("en-US".parse().unwrap())
+    ///             .with_persisted_language::<LocalStorage>("language".to_string())
+    ///     });
+    ///
+    ///     rsx! {}
+    /// }
+    /// ```
+    pub fn with_persisted_language<S>(mut self, key: S::Key) -> Self
+    where
+        S: StorageBacking + StorageSubscriber<S>,
+        S::Key: Clone,
+    {
+        self.selected_language_signal = Some(Box::new(move |selected_language| {
+            new_synced_storage::<S, LanguageIdentifier>(key, || selected_language)
+        }));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{use_init_i18n_with_config, UseI18};
+    use crate::storage::tests::TestStorage;
+    use dioxus::dioxus_core::NoOpMutations;
+    use dioxus::prelude::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static I18: RefCell<Option<UseI18>> = const { RefCell::new(None) };
+    }
+
+    fn app() -> Element {
+        let i18 = use_init_i18n_with_config(|| {
+            I18nConfig::new("en-US".parse().unwrap())
+                .with_persisted_language::<TestStorage>("language".to_string())
+        });
+        use_hook(|| I18.with(|stored| *stored.borrow_mut() = Some(i18)));
+        rsx! {}
+    }
+
+    fn stored_language() -> Option<LanguageIdentifier> {
+        TestStorage::get(&"language".to_string())
+    }
+
+    #[test]
+    fn restores_and_stores_the_language() {
+        let language: LanguageIdentifier = "es-ES".parse().unwrap();
+        TestStorage::set("language".to_string(), &language);
+
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        let mut i18 = I18.with(|i18| i18.borrow().unwrap());
+        // The stored language takes precedence over the configured one
+        assert_eq!(*i18.selected_language.peek(), language);
+
+        dom.in_runtime(|| ScopeId::ROOT.in_runtime(|| i18.set_language("fr-FR".parse().unwrap())));
+        dom.process_events();
+        dom.render_immediate(&mut NoOpMutations);
+        assert_eq!(stored_language(), Some("fr-FR".parse().unwrap()));
+    }
+}
@@ -0,0 +1,245 @@
+//! Plural category selection for translations.
+
+use icu_plurals::{PluralOperands, PluralRuleType, PluralRules};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use unic_langid::LanguageIdentifier;
+
+pub use icu_plurals::PluralCategory;
+
+/// The name of the parameter that selects the plural variant of a text.
+pub const PLURAL_PARAM: &str = "count";
+
+/// A text with one variant per [CLDR plural category](https://cldr.unicode.org/index/cldr-spec/plural-rules).
+///
+/// The variant is chosen with the plural rules of the language the text belongs to, using the number passed as the `count` parameter.
+/// Only `other` is required; any category without a variant falls back to `other`.
+///
+/// In JSON this is an object whose keys are plural categories:
+///
+/// ```json
+/// {
+///     "files": {
+///         "one": "{count} file",
+///         "other": "{count} files"
+///     }
+/// }
+/// ```
+///
+/// Any object with an `other` text whose keys are all plural categories is read as a plural text rather than a group of texts,
+/// so a group can't consist only of texts named `zero`, `one`, `two`, `few`, `many` and `other`.
+/// The variants of a plural text aren't keys of their own, `files.one` is missing in the example above.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Plurals {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zero: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub one: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub two: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub few: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub many: Option<String>,
+    pub other: String,
+}
+
+impl Plurals {
+    /// Selects the variant for the given count according to the plural rules of the given language.
+    ///
+    /// Falls back to `other` when the count is missing or isn't a number, or when there are no plural rules for the language.
+    pub fn select(&self, language: &LanguageIdentifier, count: Option<&str>) -> &str {
+        let variant = match count.and_then(|count| plural_category(language, count)) {
+            Some(PluralCategory::Zero) => self.zero.as_ref(),
+            Some(PluralCategory::One) => self.one.as_ref(),
+            Some(PluralCategory::Two) => self.two.as_ref(),
+            Some(PluralCategory::Few) => self.few.as_ref(),
+            Some(PluralCategory::Many) => self.many.as_ref(),
+            Some(PluralCategory::Other) | None => None,
+        };
+        variant.unwrap_or(&self.other)
+    }
+}
+
+/// Gets the name of a plural category, as used for the keys of plural texts and the variants of messages.
+pub(crate) fn category_name(category: PluralCategory) -> &'static str {
+    match category {
+        PluralCategory::Zero => "zero",
+        PluralCategory::One => "one",
+        PluralCategory::Two => "two",
+        PluralCategory::Few => "few",
+        PluralCategory::Many => "many",
+        PluralCategory::Other => "other",
+    }
+}
+
+/// Gets the cardinal plural category of a number in the given language.
+pub fn plural_category(language: &LanguageIdentifier, count: &str) -> Option<PluralCategory> {
+    category(language, count, PluralRuleType::Cardinal)
+}
+
+/// Gets the ordinal plural category of a number in the given language, e.g. [`PluralCategory::Two`] for `2nd` in English.
+pub fn ordinal_category(language: &LanguageIdentifier, count: &str) -> Option<PluralCategory> {
+    category(language, count, PluralRuleType::Ordinal)
+}
+
+/// The plural rules of every language they were needed for, or `None` when there are none.
+type RulesCache = HashMap<(LanguageIdentifier, PluralRuleType), Option<Rc<PluralRules>>>;
+
+thread_local! {
+    // The rules are created once per language, as formatting a message can select many categories.
+    static RULES: RefCell<RulesCache> = RefCell::default();
+}
+
+fn category(
+    language: &LanguageIdentifier,
+    count: &str,
+    rule_type: PluralRuleType,
+) -> Option<PluralCategory> {
+    let operands = count.parse::<PluralOperands>().ok()?;
+    let rules = RULES.with(|rules| {
+        rules
+            .borrow_mut()
+            .entry((language.clone(), rule_type))
+            .or_insert_with(|| {
+                // Regional languages without rules of their own (`pl-PL`) fall back to the rules of their language.
+                let locale: icu_locid::LanguageIdentifier =
+                    language.to_string().parse().unwrap_or_default();
+                PluralRules::try_new(&(&locale).into(), rule_type)
+                    .ok()
+                    .map(Rc::new)
+            })
+            .clone()
+    })?;
+    Some(rules.category_for(operands))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    fn text(language: &Language, count: &str) -> String {
+        let params = HashMap::from([(PLURAL_PARAM, count.to_string())]);
+        language.get_text("files", params).unwrap()
+    }
+
+    #[test]
+    fn english() {
+        let language = Language::from_str(
+            r#"{ "id": "en-US", "texts": { "files": { "one": "{count} file", "other": "{count} files" } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(text(&language, "0"), "0 files");
+        assert_eq!(text(&language, "1"), "1 file");
+        assert_eq!(text(&language, "2"), "2 files");
+        assert_eq!(text(&language, "1.5"), "1.5 files");
+    }
+
+    #[test]
+    fn polish() {
+        let language = Language::from_str(
+            r#"{ "id": "pl-PL", "texts": { "files": { "one": "{count} plik", "few": "{count} pliki", "many": "{count} plików", "other": "{count} pliku" } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(text(&language, "1"), "1 plik");
+        assert_eq!(text(&language, "2"), "2 pliki");
+        assert_eq!(text(&language, "4"), "4 pliki");
+        assert_eq!(text(&language, "5"), "5 plików");
+        assert_eq!(text(&language, "12"), "12 plików");
+        assert_eq!(text(&language, "22"), "22 pliki");
+        assert_eq!(text(&language, "25"), "25 plików");
+        assert_eq!(text(&language, "1.5"), "1.5 pliku");
+    }
+
+    #[test]
+    fn arabic() {
+        let language = Language::from_str(
+            r#"{ "id": "ar", "texts": { "files": { "zero": "zero", "one": "one", "two": "two", "few": "few", "many": "many", "other": "other" } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(text(&language, "0"), "zero");
+        assert_eq!(text(&language, "1"), "one");
+        assert_eq!(text(&language, "2"), "two");
+        assert_eq!(text(&language, "3"), "few");
+        assert_eq!(text(&language, "10"), "few");
+        assert_eq!(text(&language, "11"), "many");
+        assert_eq!(text(&language, "99"), "many");
+        assert_eq!(text(&language, "100"), "other");
+        assert_eq!(text(&language, "102"), "other");
+        assert_eq!(text(&language, "103"), "few");
+        assert_eq!(text(&language, "110"), "few");
+        assert_eq!(text(&language, "111"), "many");
+        assert_eq!(text(&language, "203"), "few");
+    }
+
+    #[test]
+    fn missing_variant_falls_back_to_other() {
+        let language = Language::from_str(
+            r#"{ "id": "ar", "texts": { "files": { "other": "{count} files" } } }"#,
+        )
+        .unwrap();
+
+        // Parameters of right-to-left languages are isolated
+        assert_eq!(text(&language, "2"), "\u{2068}2\u{2069} files");
+        assert_eq!(
+            language.get_text("files", HashMap::default()).unwrap(),
+            "{count} files"
+        );
+    }
+
+    #[test]
+    fn reads_objects_of_plural_categories_as_plurals() {
+        let language = Language::from_str(
+            r#"{ "id": "en-US", "texts": {
+                "files": { "one": "{count} file", "other": "{count} files" },
+                "numbers": { "one": "One", "three": "Three" },
+                "levels": { "one": "First" },
+                "nested": { "other": { "one": "Nested" } }
+            } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(text(&language, "1"), "1 file");
+        assert_eq!(language.get_text("files.one", HashMap::new()), None);
+        // Objects with other keys, without `other` or with groups inside are groups
+        assert_eq!(
+            language.get_text("numbers.one", HashMap::new()).as_deref(),
+            Some("One")
+        );
+        assert_eq!(
+            language.get_text("levels.one", HashMap::new()).as_deref(),
+            Some("First")
+        );
+        assert_eq!(
+            language
+                .get_text("nested.other.one", HashMap::new())
+                .as_deref(),
+            Some("Nested")
+        );
+    }
+
+    #[cfg(feature = "i18n_fluent")]
+    #[test]
+    fn fluent_selects_with_the_same_rules() {
+        let language = Language::from_ftl(
+            "ar".parse().unwrap(),
+            "files = { $count ->\n    [0] none\n    [few] few\n    [many] many\n   *[other] other\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(text(&language, "0"), "none");
+        assert_eq!(text(&language, "1"), "other");
+        assert_eq!(text(&language, "3"), "few");
+        assert_eq!(text(&language, "11"), "many");
+        assert_eq!(text(&language, "103"), "few");
+        assert_eq!(text(&language, "111"), "many");
+        assert_eq!(text(&language, "abc"), "other");
+    }
+}
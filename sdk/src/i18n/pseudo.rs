@@ -0,0 +1,256 @@
+//! Pseudo-locales for finding untranslated texts and layout issues.
+
+use std::fmt;
+use unic_langid::LanguageIdentifier;
+
+use super::direction::{text_direction, TextDirection};
+use super::use_init_i18n::{I18nConfig, UseInitI18Data};
+
+/// A pseudo-locale that transforms the texts of another language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PseudoLocale {
+    /// `en-XA`, accents the letters and makes texts longer and bracketed, e.g. `[Ħḗḗŀŀǿǿ]`.
+    Accented,
+    /// `ar-XB`, mirrors texts by writing them right to left.
+    Bidi,
+}
+
+impl PseudoLocale {
+    /// Gets the pseudo-locale with the given identifier.
+    pub fn from_id(id: &LanguageIdentifier) -> Option<Self> {
+        match (
+            id.language.as_str(),
+            id.region.as_ref().map(|region| region.as_str()),
+        ) {
+            ("en", Some("XA")) => Some(PseudoLocale::Accented),
+            ("ar", Some("XB")) => Some(PseudoLocale::Bidi),
+            _ => None,
+        }
+    }
+
+    /// Gets the identifier of this pseudo-locale.
+    pub fn id(&self) -> LanguageIdentifier {
+        let id = match self {
+            PseudoLocale::Accented => "en-XA",
+            PseudoLocale::Bidi => "ar-XB",
+        };
+        id.parse().expect("the pseudo-locale is a valid identifier")
+    }
+
+    /// Gets the direction texts are written in with this pseudo-locale, which isn't the direction of the language it transforms.
+    pub(crate) fn direction(&self) -> TextDirection {
+        text_direction(&self.id())
+    }
+
+    /// Transforms a text, keeping its markup tags, entities and placeholders as they are.
+    ///
+    /// ```rust
+    /// use dioxus_sdk::i18n::PseudoLocale;
+    ///
+    /// assert_eq!(PseudoLocale::Accented.apply("Hello <b>{name}</b>"), "[Ħḗḗŀŀǿǿ <b>{name}</b>]");
+    /// ```
+    pub fn apply(&self, text: &str) -> String {
+        self.wrap(self.transform(text))
+    }
+
+    /// Transforms a part of a text, without the brackets [`PseudoLocale::Accented`] puts around whole texts.
+    ///
+    /// Translations transform the parts of their messages this way, so the values of parameters aren't transformed.
+    pub(crate) fn transform(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len() * 2);
+        match self {
+            PseudoLocale::Accented => {
+                for_each_text(text, &mut result, |text, result| {
+                    for c in text.chars() {
+                        let accented = accent(c);
+                        result.push(accented);
+                        // Make texts about a third longer, like many translations are
+                        if "aeiouAEIOU".contains(c) {
+                            result.push(accented);
+                        }
+                    }
+                });
+            }
+            PseudoLocale::Bidi => {
+                for_each_text(text, &mut result, |text, result| {
+                    // Override the direction of the text, so its letters are laid out right to left
+                    if !text.trim().is_empty() {
+                        result.push_str(&format!("\u{202E}{text}\u{202C}"));
+                    } else {
+                        result.push_str(text);
+                    }
+                });
+            }
+        }
+        result
+    }
+
+    /// Finishes a text whose parts were [transformed](PseudoLocale::transform).
+    pub(crate) fn wrap(&self, text: String) -> String {
+        match self {
+            PseudoLocale::Accented => format!("[{text}]"),
+            PseudoLocale::Bidi => text,
+        }
+    }
+}
+
+impl fmt::Display for PseudoLocale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+const LOWERCASE: [char; 26] = [
+    'ȧ', 'ƀ', 'ƈ', 'ḓ', 'ḗ', 'ƒ', 'ɠ', 'ħ', 'ī', 'ĵ', 'ķ', 'ŀ', 'ḿ', 'ƞ', 'ǿ', 'ƥ', 'ɋ', 'ř', 'ş',
+    'ŧ', 'ŭ', 'ṽ', 'ẇ', 'ẋ', 'ẏ', 'ẑ',
+];
+const UPPERCASE: [char; 26] = [
+    'Ȧ', 'Ɓ', 'Ƈ', 'Ḓ', 'Ḗ', 'Ƒ', 'Ɠ', 'Ħ', 'Ī', 'Ĵ', 'Ķ', 'Ŀ', 'Ḿ', 'Ƞ', 'Ǿ', 'Ƥ', 'Ɋ', 'Ř', 'Ş',
+    'Ŧ', 'Ŭ', 'Ṽ', 'Ẇ', 'Ẋ', 'Ẏ', 'Ẑ',
+];
+
+fn accent(c: char) -> char {
+    match c {
+        'a'..='z' => LOWERCASE[c as usize - 'a' as usize],
+        'A'..='Z' => UPPERCASE[c as usize - 'A' as usize],
+        _ => c,
+    }
+}
+
+/// Transforms the parts of a text between its `<tag>`s, `&entity;`s and `{placeholder}`s.
+fn for_each_text(text: &str, result: &mut String, mut transform: impl FnMut(&str, &mut String)) {
+    let mut rest = text;
+    while let Some(start) = rest.find(['<', '&', '{']) {
+        let close = match &rest[start..start + 1] {
+            "<" => '>',
+            "&" => ';',
+            _ => '}',
+        };
+        // Tags and entities have no spaces, so e.g. `1 < 2 > 0` stays text
+        let end = rest[start..]
+            .find(close)
+            .map(|end| start + end + 1)
+            .filter(|&end| close == '}' || !rest[start..end].contains(char::is_whitespace));
+        let Some(end) = end else {
+            transform(&rest[..start + 1], result);
+            rest = &rest[start + 1..];
+            continue;
+        };
+        transform(&rest[..start], result);
+        result.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    transform(rest, result);
+}
+
+impl I18nConfig {
+    /// Enables the [pseudo-locales](PseudoLocale), so `en-XA` and `ar-XB` can be selected with [`UseI18::set_language`](super::UseI18::set_language).
+    ///
+    /// Their texts are generated from the first fallback language, so texts that aren't translated stand out because they aren't transformed.
+    pub fn with_pseudo_locales(mut self) -> Self {
+        self.pseudo_locales = true;
+        self
+    }
+}
+
+impl UseInitI18Data {
+    /// Gets the pseudo-locale with the given identifier and the language its texts are generated from, if pseudo-locales are enabled.
+    pub(crate) fn pseudo_locale(
+        &self,
+        id: &LanguageIdentifier,
+    ) -> Option<(PseudoLocale, LanguageIdentifier)> {
+        if !self.pseudo_locales {
+            return None;
+        }
+        let pseudo_locale = PseudoLocale::from_id(id)?;
+        let base = self
+            .fallback_languages
+            .first()
+            .cloned()
+            .unwrap_or_else(|| id.clone());
+        Some((pseudo_locale, base))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    #[test]
+    fn accents_and_expands_texts() {
+        assert_eq!(
+            PseudoLocale::Accented.apply("Save {count} files & folders"),
+            "[Şȧȧṽḗḗ {count} ƒīīŀḗḗş & ƒǿǿŀḓḗḗřş]"
+        );
+        assert_eq!(
+            PseudoLocale::Accented.apply("<link>Tom</link>&amp;"),
+            "[<link>Ŧǿǿḿ</link>&amp;]"
+        );
+    }
+
+    #[test]
+    fn mirrors_texts() {
+        assert_eq!(
+            PseudoLocale::Bidi.apply("Hi <b>you</b>"),
+            "\u{202E}Hi \u{202C}<b>\u{202E}you\u{202C}</b>"
+        );
+        assert_eq!(
+            PseudoLocale::from_id(&"ar-XB".parse().unwrap()),
+            Some(PseudoLocale::Bidi)
+        );
+        assert_eq!(PseudoLocale::from_id(&"en-US".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn keeps_parameter_values() {
+        let json = Language::from_str(
+            r#"{ "id": "en-US", "texts": { "hello": "Hello {name}", "files": { "one": "{count} file", "other": "{count} files" } } }"#,
+        )
+        .unwrap();
+        let pseudo = Some(PseudoLocale::Accented);
+
+        let params = || HashMap::from([("name", "Ana".to_string()), ("count", "1".to_string())]);
+        let text = json.format_text("hello", params(), pseudo, &mut Vec::new());
+        assert_eq!(text.as_deref(), Some("Ħḗḗŀŀǿǿ Ana"));
+        #[cfg(feature = "i18n_fluent")]
+        {
+            let ftl =
+                Language::from_ftl("en-US".parse().unwrap(), "hello = Hello { $name }").unwrap();
+            let text = ftl.format_text("hello", params(), pseudo, &mut Vec::new());
+            assert_eq!(text.as_deref(), Some("Ħḗḗŀŀǿǿ Ana"));
+        }
+        let text = json.format_text("files", params(), pseudo, &mut Vec::new());
+        assert_eq!(text.as_deref(), Some("1 ƒīīŀḗḗ"));
+    }
+
+    #[test]
+    fn isolates_parameters_in_the_direction_of_the_pseudo_locale() {
+        let json =
+            Language::from_str(r#"{ "id": "en-US", "texts": { "hello": "Hi {name}" } }"#).unwrap();
+        let params = || HashMap::from([("name", "Ana".to_string())]);
+        let mirrored = Some("\u{202E}Hi \u{202C}\u{2068}Ana\u{2069}");
+
+        // Right-to-left texts isolate their parameters, even when the language they are generated from is left-to-right
+        let text = json.format_text("hello", params(), Some(PseudoLocale::Bidi), &mut Vec::new());
+        assert_eq!(text.as_deref(), mirrored);
+        let text = json.format_text(
+            "hello",
+            params(),
+            Some(PseudoLocale::Accented),
+            &mut Vec::new(),
+        );
+        assert_eq!(text.as_deref(), Some("Ħīī Ana"));
+        #[cfg(feature = "i18n_fluent")]
+        {
+            let ftl = Language::from_ftl("en-US".parse().unwrap(), "hello = Hi { $name }").unwrap();
+            let text =
+                ftl.format_text("hello", params(), Some(PseudoLocale::Bidi), &mut Vec::new());
+            assert_eq!(text.as_deref(), mirrored);
+            let text = ftl.format_text("hello", params(), None, &mut Vec::new());
+            assert_eq!(text.as_deref(), Some("Hi Ana"));
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Translations with markup that is rendered as elements.
+
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+use super::use_i18n::UseI18;
+
+/// A node of a text with markup.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MarkupNode {
+    Text(String),
+    /// A `<name>...</name>` or `<name/>` tag.
+    Tag {
+        name: String,
+        children: Vec<MarkupNode>,
+    },
+}
+
+/// Parses a text with `<name>...</name>` and `<name/>` tags.
+///
+/// `&lt;`, `&gt;`, `&amp;`, `&quot;` and `&apos;` are unescaped.
+/// Tags that aren't closed or closing tags that weren't opened are kept as text.
+pub(crate) fn parse_markup(text: &str) -> Vec<MarkupNode> {
+    // The open tags, starting with the root which has no name
+    let mut stack: Vec<(&str, Vec<MarkupNode>)> = vec![("", Vec::new())];
+    let mut buffer = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(['<', '&']) {
+        buffer.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(stripped) = rest.strip_prefix('&') {
+            let entity = stripped.split_once(';').and_then(|(name, _)| {
+                let c = match name {
+                    "lt" => '<',
+                    "gt" => '>',
+                    "amp" => '&',
+                    "quot" => '"',
+                    "apos" => '\'',
+                    _ => return None,
+                };
+                Some((c, name.len() + 2))
+            });
+            let (c, len) = entity.unwrap_or(('&', 1));
+            buffer.push(c);
+            rest = &rest[len..];
+            continue;
+        }
+
+        let Some((tag, len)) = rest
+            .find('>')
+            .and_then(|end| Some((Tag::parse(&rest[1..end])?, end + 1)))
+        else {
+            buffer.push('<');
+            rest = &rest[1..];
+            continue;
+        };
+        match tag {
+            Tag::Open(name) => {
+                flush(&mut buffer, &mut stack);
+                stack.push((name, Vec::new()));
+            }
+            Tag::SelfClosing(name) => {
+                flush(&mut buffer, &mut stack);
+                push(
+                    &mut stack,
+                    MarkupNode::Tag {
+                        name: name.to_string(),
+                        children: Vec::new(),
+                    },
+                );
+            }
+            Tag::Close(name) if stack.len() > 1 && stack[stack.len() - 1].0 == name => {
+                flush(&mut buffer, &mut stack);
+                let (name, children) = stack.pop().expect("the tag is open");
+                push(
+                    &mut stack,
+                    MarkupNode::Tag {
+                        name: name.to_string(),
+                        children,
+                    },
+                );
+            }
+            Tag::Close(_) => buffer.push_str(&rest[..len]),
+        }
+        rest = &rest[len..];
+    }
+    buffer.push_str(rest);
+    flush(&mut buffer, &mut stack);
+
+    // Keep tags that weren't closed as text
+    while stack.len() > 1 {
+        let (name, children) = stack.pop().expect("the tag is open");
+        push(&mut stack, MarkupNode::Text(format!("<{name}>")));
+        for child in children {
+            push(&mut stack, child);
+        }
+    }
+    stack.pop().map(|(_, nodes)| nodes).unwrap_or_default()
+}
+
+enum Tag<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    SelfClosing(&'a str),
+}
+
+impl<'a> Tag<'a> {
+    fn parse(inner: &'a str) -> Option<Self> {
+        let (tag, name): (fn(&'a str) -> Self, &str) = if let Some(name) = inner.strip_prefix('/') {
+            (Tag::Close, name)
+        } else if let Some(name) = inner.strip_suffix('/') {
+            (Tag::SelfClosing, name.trim_end())
+        } else {
+            (Tag::Open, inner)
+        };
+        let is_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        is_name.then(|| tag(name))
+    }
+}
+
+fn push(stack: &mut [(&str, Vec<MarkupNode>)], node: MarkupNode) {
+    let (_, nodes) = stack.last_mut().expect("the root is never popped");
+    // Merge adjacent texts, e.g. around unescaped entities
+    if let (Some(MarkupNode::Text(last)), MarkupNode::Text(text)) = (nodes.last_mut(), &node) {
+        last.push_str(text);
+    } else {
+        nodes.push(node);
+    }
+}
+
+fn flush(buffer: &mut String, stack: &mut [(&str, Vec<MarkupNode>)]) {
+    if !buffer.is_empty() {
+        push(stack, MarkupNode::Text(std::mem::take(buffer)));
+    }
+}
+
+/// Escapes a text so it isn't parsed as markup.
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;")
+}
+
+fn render_nodes(nodes: &[MarkupNode], render_tag: &dyn Fn(&str, Element) -> Element) -> Element {
+    rsx! {
+        for node in nodes {
+            {render_node(node, render_tag)}
+        }
+    }
+}
+
+fn render_node(node: &MarkupNode, render_tag: &dyn Fn(&str, Element) -> Element) -> Element {
+    match node {
+        MarkupNode::Text(text) => rsx! { "{text}" },
+        MarkupNode::Tag { name, children } => render_tag(name, render_nodes(children, render_tag)),
+    }
+}
+
+impl UseI18 {
+    /// Translates a text with markup, rendering its tags with the given function.
+    ///
+    /// `render_tag` receives the name of a tag and its rendered children, and returns the element to render in its place.
+    /// The parameters are escaped, so they are never parsed as markup.
+    /// Prefer the [`translate_rsx!`](crate::translate_rsx) macro.
+    pub fn translate_rsx(
+        &self,
+        id: &str,
+        params: HashMap<&str, String>,
+        render_tag: impl Fn(&str, Element) -> Element,
+    ) -> Element {
+        let params = params
+            .into_iter()
+            .map(|(name, value)| (name, escape_markup(&value)))
+            .collect();
+        let text = self.translate_with_params(id, params);
+        render_nodes(&parse_markup(&text), &render_tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> MarkupNode {
+        MarkupNode::Text(text.to_string())
+    }
+
+    fn tag(name: &str, children: Vec<MarkupNode>) -> MarkupNode {
+        MarkupNode::Tag {
+            name: name.to_string(),
+            children,
+        }
+    }
+
+    #[test]
+    fn parses_tags() {
+        assert_eq!(
+            parse_markup("Accept the <link>terms of <b>service</b></link>.<br/>"),
+            vec![
+                text("Accept the "),
+                tag(
+                    "link",
+                    vec![text("terms of "), tag("b", vec![text("service")])]
+                ),
+                text("."),
+                tag("br", Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_invalid_tags_as_text() {
+        assert_eq!(
+            parse_markup("1 < 2 &amp; <b>bold</i> &lt;b&gt; &nbsp;"),
+            vec![text("1 < 2 & <b>bold</i> <b> &nbsp;")]
+        );
+        assert_eq!(
+            parse_markup(&escape_markup("<b>Tom & Jerry</b>")),
+            vec![text("<b>Tom & Jerry</b>")]
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Namespaces that let component libraries ship their own translations.
+
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+use super::use_i18n::{use_i18, Language, UseI18};
+use super::use_init_i18n::I18nConfig;
+
+impl I18nConfig {
+    /// Adds the languages of a namespace, e.g. the translations shipped by a component library.
+    ///
+    /// A key `title` of a namespace `billing` is translated with the key `billing.title`, see [`use_i18_scope`].
+    /// The app's languages take precedence, so the app overrides a string of the namespace by adding `billing.title` itself.
+    /// Keys that the app doesn't have are taken from the namespace's languages, which also follow the selected and fallback languages.
+    ///
+    /// Adding languages to the same namespace again adds them to the ones it already has.
+    ///
+    /// ```rust
+    /// use dioxus_sdk::i18n::{I18nConfig, Language};
+    ///
+    /// let billing = Language::from_json(r#"{ "id": "en-US", "texts": { "title": "Invoices" } }"#).unwrap();
+    /// let config = I18nConfig::new("en-US".parse().unwrap()).with_namespace("billing", [billing]);
+    /// ```
+    pub fn with_namespace(
+        mut self,
+        name: impl Into<String>,
+        languages: impl IntoIterator<Item = Language>,
+    ) -> Self {
+        self.namespaces
+            .entry(name.into())
+            .or_default()
+            .extend(languages);
+        self
+    }
+}
+
+/// Translates the keys of a namespace, see [`use_i18_scope`].
+#[derive(Clone, Copy, PartialEq)]
+pub struct UseI18Scope {
+    pub i18: UseI18,
+    namespace: &'static str,
+}
+
+impl UseI18Scope {
+    /// Gets the namespace keys are translated in.
+    pub fn namespace(&self) -> &'static str {
+        self.namespace
+    }
+
+    pub fn translate_with_params(&self, id: &str, params: HashMap<&str, String>) -> String {
+        self.i18
+            .translate_with_params(&format!("{}.{id}", self.namespace), params)
+    }
+
+    pub fn translate(&self, id: &str) -> String {
+        self.translate_with_params(id, HashMap::default())
+    }
+}
+
+/// A hook that translates keys in the given namespace, see [`I18nConfig::with_namespace`].
+///
+/// This works with the [`translate!`](crate::translate) macro like [`use_i18`].
+///
+/// # Example
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::i18n::use_i18_scope;
+/// use dioxus_sdk::translate;
+///
+/// #[component]
+/// fn InvoiceHeader() -> Element {
+///     let i18 = use_i18_scope("billing");
+///
+///     // Translates the key `billing.title`
+///     rsx! { h1 { {translate!(i18, "title")} } }
+/// }
+/// ```
+pub fn use_i18_scope(namespace: &'static str) -> UseI18Scope {
+    let i18 = use_i18();
+    use_hook(|| UseI18Scope { i18, namespace })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::use_init_i18n_with_config;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static TEXTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn language(json: &str) -> Language {
+        Language::from_json(json).unwrap()
+    }
+
+    fn app() -> Element {
+        use_init_i18n_with_config(|| {
+            I18nConfig::new("es-ES".parse().unwrap())
+                .with_fallback("en-US".parse().unwrap())
+                .with_language(language(
+                    r#"{ "id": "es-ES", "texts": { "hello": "Hola", "billing": { "title": "Mis facturas" } } }"#,
+                ))
+                .with_namespace(
+                    "billing",
+                    [
+                        language(r#"{ "id": "en-US", "texts": { "title": "Invoices", "total": "Total" } }"#),
+                        language(r#"{ "id": "es-ES", "texts": { "title": "Facturas", "total": "Total a pagar" } }"#),
+                    ],
+                )
+        });
+        rsx! { Invoice {} }
+    }
+
+    #[allow(non_snake_case)]
+    fn Invoice() -> Element {
+        let scope = use_i18_scope("billing");
+        let i18 = use_i18();
+        TEXTS.with(|texts| {
+            *texts.borrow_mut() = vec![
+                scope.translate("title"),
+                scope.translate("total"),
+                scope.translate("hello"),
+                i18.translate("total"),
+                i18.translate("billing.total"),
+            ]
+        });
+        rsx! {}
+    }
+
+    #[test]
+    fn translates_keys_of_the_namespace() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+
+        let texts = TEXTS.with(|texts| texts.borrow().clone());
+        assert_eq!(
+            texts,
+            [
+                // The app overrides the namespace's text
+                "Mis facturas",
+                // Keys the app doesn't have come from the namespace, in the selected language
+                "Total a pagar",
+                // Keys in a scope are always prefixed, so the app's unprefixed key isn't used
+                "billing.hello",
+                // Outside of a scope, the keys of a namespace need its prefix
+                "total",
+                "Total a pagar",
+            ]
+        );
+    }
+}
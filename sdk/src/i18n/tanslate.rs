@@ -16,3 +16,43 @@ macro_rules! translate {
         }
     };
 }
+
+/// Translates a text with markup into an [`Element`](dioxus::prelude::Element), see [`UseI18::translate_rsx`](crate::i18n::UseI18::translate_rsx).
+///
+/// Parameters are passed like with [`translate!`], followed by a `;` and a function for every tag that
+/// receives the rendered children of the tag. Tags without a function only render their children.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::{i18n::use_i18, translate_rsx};
+///
+/// // "terms": "Hi {name}, please accept the <link>terms of <b>service</b></link>."
+/// fn Terms() -> Element {
+///     let i18 = use_i18();
+///     translate_rsx!(i18, "terms", name: "Dioxus";
+///         link: |children| rsx! { a { href: "/terms", {children} } },
+///         b: |children| rsx! { b { {children} } },
+///     )
+/// }
+/// ```
+#[macro_export]
+macro_rules! translate_rsx {
+    ( $i18:expr, $id:expr $(, $name:ident : $value:expr )* ; $( $tag:ident : $render:expr ),* $(,)? ) => {
+        {
+            #[allow(unused_mut)]
+            let mut params_map = std::collections::HashMap::new();
+            $(
+                params_map.insert(stringify!($name), $value.to_string());
+            )*
+            $i18.translate_rsx($id, params_map, |tag, children| match tag {
+                $(
+                    stringify!($tag) => {
+                        let render: &dyn Fn(::dioxus::prelude::Element) -> ::dioxus::prelude::Element = &$render;
+                        render(children)
+                    }
+                )*
+                _ => children,
+            })
+        }
+    };
+}
@@ -1,20 +1,89 @@
 use dioxus::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, fmt, str::FromStr};
 use unic_langid::LanguageIdentifier;
 
+#[cfg(feature = "i18n_fluent")]
+use super::fluent::FluentMessages;
+use super::missing::{MissingPolicy, MissingTranslation};
+use super::negotiate::fallback_chain;
+use super::plural::{Plurals, PLURAL_PARAM};
+use super::pseudo::PseudoLocale;
 use super::use_init_i18n::UseInitI18Data;
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+// Fluent messages are serialized as the source of their resource
+#[cfg_attr(
+    feature = "i18n_fluent",
+    serde(
+        try_from = "super::fluent::SerializedLanguage",
+        into = "super::fluent::SerializedLanguage"
+    )
+)]
 pub struct Language {
-    id: LanguageIdentifier,
-    texts: Text,
+    pub(crate) id: LanguageIdentifier,
+    pub(crate) texts: Text,
+    /// Messages loaded from a Fluent resource, see [`Language::from_ftl`].
+    #[cfg(feature = "i18n_fluent")]
+    #[serde(skip)]
+    pub(crate) fluent: Option<FluentMessages>,
+}
+
+/// Represents errors when loading a language.
+#[derive(Debug)]
+pub enum LanguageError {
+    /// The JSON language could not be parsed.
+    Json(serde_json::Error),
+    /// The Fluent resource could not be parsed or added to the bundle.
+    #[cfg(feature = "i18n_fluent")]
+    Fluent(Vec<fluent_bundle::FluentError>),
+    /// The language file could not be read.
+    Io(std::io::Error),
+    /// The language could not be fetched.
+    Fetch(String),
+    /// The gettext catalog could not be parsed.
+    Gettext(String),
+    /// The loaded language has another identifier than the one that was requested.
+    WrongId {
+        requested: LanguageIdentifier,
+        loaded: LanguageIdentifier,
+    },
+}
+
+impl std::error::Error for LanguageError {}
+impl fmt::Display for LanguageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LanguageError::Json(e) => write!(f, "{}", e),
+            #[cfg(feature = "i18n_fluent")]
+            LanguageError::Fluent(errors) => {
+                let errors = errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "{}", errors)
+            }
+            LanguageError::Io(e) => write!(f, "{}", e),
+            LanguageError::Fetch(s) => write!(f, "{}", s),
+            LanguageError::Gettext(s) => write!(f, "{}", s),
+            LanguageError::WrongId { requested, loaded } => write!(
+                f,
+                "language \"{}\" was requested but \"{}\" was loaded",
+                requested, loaded
+            ),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum Text {
     Value(String),
+    /// A text with a variant per plural category, see [`Plurals`].
+    ///
+    /// Objects whose keys are all plural categories are read as plural texts instead of groups.
+    Plural(Plurals),
     Texts(HashMap<String, Text>),
 }
 
@@ -25,7 +94,7 @@ impl Default for Text {
 }
 
 impl Text {
-    fn query(&self, steps: &mut Vec<&str>) -> Option<String> {
+    fn query(&self, steps: &mut Vec<&str>) -> Option<&Text> {
         match self {
             Text::Texts(texts) => {
                 if steps.is_empty() {
@@ -36,8 +105,7 @@ impl Text {
 
                 // Try querying the next step in this list
                 let this_step = steps.remove(0);
-                let deep = texts.get(this_step)?;
-                let res = deep.query(steps);
+                let res = texts.get(this_step).and_then(|deep| deep.query(steps));
 
                 // If not found try querying by the whole remaining path as if it was the ID
                 if res.is_none() {
@@ -48,7 +116,11 @@ impl Text {
                 }
                 res
             }
-            Text::Value(value) => Some(value.to_owned()),
+            // The variants of a plural text aren't texts of their own
+            Text::Plural(_) if !steps.is_empty() => None,
+            // The variants of a plural text aren't texts of their own
+            Text::Plural(_) if !steps.is_empty() => None,
+            Text::Value(_) | Text::Plural(_) => Some(self),
         }
     }
 }
@@ -62,15 +134,61 @@ impl FromStr for Language {
 }
 
 impl Language {
+    /// Creates a language from a JSON string, returning the parse error on failure.
+    pub fn from_json(s: &str) -> Result<Self, LanguageError> {
+        serde_json::from_str(s).map_err(LanguageError::Json)
+    }
+
+    /// Gets the identifier of this language.
+    pub fn id(&self) -> &LanguageIdentifier {
+        &self.id
+    }
+
     pub fn get_text(&self, path: &str, params: HashMap<&str, String>) -> Option<String> {
+        self.format_text(path, params, None, &mut Vec::new())
+    }
+
+    /// Gets the text at the given path, adding the parameters it uses that aren't in `params` to `missing_params`.
+    ///
+    /// With a pseudo-locale the parts of the text are transformed, see [`PseudoLocale::transform`].
+    pub(crate) fn format_text(
+        &self,
+        path: &str,
+        params: HashMap<&str, String>,
+        pseudo_locale: Option<PseudoLocale>,
+        missing_params: &mut Vec<String>,
+    ) -> Option<String> {
+        #[cfg(feature = "i18n_fluent")]
+        if let Some(fluent) = &self.fluent {
+            if let Some(text) = fluent.format(path, &params, pseudo_locale, missing_params) {
+                return Some(text);
+            }
+        }
+
         let mut steps = path.split('.').collect::<Vec<&str>>();
 
-        let mut text = self.texts.query(&mut steps).unwrap_or_default();
+        let text = match self.texts.query(&mut steps) {
+            Some(Text::Value(value)) => value.as_str(),
+            Some(Text::Plural(plurals)) => {
+                plurals.select(&self.id, params.get(PLURAL_PARAM).map(String::as_str))
+            }
+            _ => return None,
+        };
 
-        for (name, value) in params {
-            text = text.replacen(&format!("{{{name}}}"), &value.to_string(), 1);
-        }
-        Some(text)
+        Some(self.format_message(text, &params, pseudo_locale, missing_params))
+    }
+
+    #[cfg(feature = "i18n_format")]
+    pub(crate) fn format_param(&self, value: &str, format: Option<&str>) -> String {
+        format
+            .and_then(|format| super::format::format_param(&self.id, value, format))
+            .unwrap_or_else(|| value.to_string())
+    }
+
+    // Formatting parameters requires the `i18n_format` feature, so they are inserted as they are.
+    #[cfg(not(feature = "i18n_format"))]
+    pub(crate) fn format_param(&self, value: &str, _format: Option<&str>) -> String {
+        value.to_string()
     }
 }
 
@@ -78,23 +196,94 @@ impl Language {
 pub struct UseI18 {
     pub selected_language: Signal<LanguageIdentifier>,
     pub data: Signal<UseInitI18Data>,
+    /// The language that is currently being loaded, if any, see [`UseI18::loading_language`].
+    pub(crate) loading: Signal<Option<LanguageIdentifier>>,
 }
 
 impl UseI18 {
     pub fn translate_with_params(&self, id: &str, params: HashMap<&str, String>) -> String {
+        let selected_language = self.selected_language.read();
+        let pseudo_locale = self.data.read().pseudo_locale(&selected_language);
+        match pseudo_locale {
+            Some((pseudo_locale, base)) => {
+                pseudo_locale.wrap(self.translate_in(&base, id, params, Some(pseudo_locale)))
+            }
+            None => self.translate_in(&selected_language, id, params, None),
+        }
+    }
+
+    /// Translates a key as if the given language was selected, transforming the texts with the pseudo-locale if given.
+    fn translate_in(
+        &self,
+        selected_language: &LanguageIdentifier,
+        id: &str,
+        params: HashMap<&str, String>,
+        pseudo_locale: Option<PseudoLocale>,
+    ) -> String {
         let i18n_data = self.data.read();
+        // Texts of a language that is still loading aren't missing yet, they are taken from the fallback languages
+        let is_loading = self.loading.read().as_ref() == Some(selected_language);
 
-        // Try searching in the selected language
-        for language in i18n_data.languages.iter() {
-            if language.id == *self.selected_language.read() {
-                return language.get_text(id, params).unwrap_or_default();
+        // Keys of a namespace are also looked up in its languages without the namespace, after the app's languages
+        let mut candidates = i18n_data
+            .languages
+            .iter()
+            .map(|language| (language, id))
+            .collect::<Vec<_>>();
+        if let Some((languages, key)) = id
+            .split_once('.')
+            .and_then(|(name, key)| Some((i18n_data.namespaces.get(name)?, key)))
+        {
+            candidates.extend(languages.iter().map(|language| (language, key)));
+        }
+
+        let translate = |&(language, key): &(&Language, &str)| {
+            let mut missing_params = Vec::new();
+            let text =
+                language.format_text(key, params.clone(), pseudo_locale, &mut missing_params)?;
+            for param in missing_params {
+                i18n_data.report_missing(MissingTranslation::Param {
+                    language: language.id().clone(),
+                    key: id.to_string(),
+                    param,
+                });
+            }
+            Some(text)
+        };
+
+        // Languages that match the selected language come first, followed by the fallback languages
+        let mut languages = fallback_chain(
+            selected_language,
+            &i18n_data.fallback_languages,
+            &candidates,
+            |(language, _)| language.id(),
+        )
+        .into_iter()
+        .peekable();
+
+        while let Some(language) =
+            languages.next_if(|(language, _)| language.id().language == selected_language.language)
+        {
+            if let Some(text) = translate(language) {
+                return text;
             }
         }
 
-        // Otherwise find in the fallback language
-        for language in i18n_data.languages.iter() {
-            if language.id == i18n_data.fallback_language {
-                return language.get_text(id, params).unwrap_or_default();
+        if !is_loading {
+            i18n_data.report_missing(MissingTranslation::Key {
+                language: selected_language.clone(),
+                key: id.to_string(),
+            });
+            match i18n_data.missing_policy {
+                MissingPolicy::Key => return id.to_string(),
+                MissingPolicy::Marker => return MissingPolicy::marker(id),
+                MissingPolicy::Fallback => {}
+            }
+        }
+
+        for language in languages {
+            if let Some(text) = translate(language) {
+                return text;
             }
         }
 
@@ -106,19 +295,20 @@ impl UseI18 {
         self.translate_with_params(id, HashMap::default())
     }
 
+    /// Selects a language, loading it first if it hasn't been loaded yet.
+    ///
+    /// The language is selected right away, so texts are taken from the fallback languages until it has loaded.
     pub fn set_language(&mut self, id: LanguageIdentifier) {
+        self.load_language(&id);
         *self.selected_language.write() = id;
     }
 }
 
+/// A hook to translate with the i18n initialized in an ancestor component, see [`try_consume_i18`](super::try_consume_i18).
+///
+/// # Panics
+///
+/// When i18n isn't initialized, see [`try_use_i18`](super::try_use_i18) for a hook that doesn't panic.
 pub fn use_i18() -> UseI18 {
-    use_hook(|| {
-        let selected_language = consume_context::<Signal<LanguageIdentifier>>();
-        let data = consume_context::<Signal<UseInitI18Data>>();
-
-        UseI18 {
-            selected_language,
-            data,
-        }
-    })
+    super::global::try_use_i18().unwrap_or_else(|err| panic!("{}", err))
 }
@@ -1,24 +1,162 @@
 use dioxus::prelude::*;
+use std::{cell::RefCell, collections::HashMap};
 use unic_langid::LanguageIdentifier;
 
-use super::use_i18n::Language;
+use super::loader::LanguageLoader;
+use super::missing::{MissingPolicy, MissingTranslation};
+use super::negotiate::negotiate_language;
+use super::use_i18n::{Language, UseI18};
 
 pub struct UseInitI18Data {
-    pub(crate) fallback_language: LanguageIdentifier,
+    pub(crate) fallback_languages: Vec<LanguageIdentifier>,
     pub(crate) languages: Vec<Language>,
+    /// The languages of each namespace, see [`I18nConfig::with_namespace`].
+    pub(crate) namespaces: HashMap<String, Vec<Language>>,
+    pub(crate) loader: Option<LanguageLoader>,
+    /// The languages the loader is loading, see [`UseI18::load_language`].
+    pub(crate) pending_loads: RefCell<Vec<LanguageIdentifier>>,
+    pub(crate) missing_policy: MissingPolicy,
+    /// Whether the pseudo-locales can be selected, see [`I18nConfig::with_pseudo_locales`].
+    pub(crate) pseudo_locales: bool,
+    /// The translations that were missing while translating, see [`UseI18::missing_translations`].
+    pub(crate) missing: RefCell<Vec<MissingTranslation>>,
+}
+
+/// The configuration used to initialize i18n with [`use_init_i18n_with_config`].
+///
+/// ```rust
+/// use dioxus_sdk::i18n::{I18nConfig, Language};
+/// use std::str::FromStr;
+/// use unic_langid::LanguageIdentifier;
+///
+/// let requested: [LanguageIdentifier; 1] = ["en-GB".parse().unwrap()];
+/// let config = I18nConfig::new("en-US".parse().unwrap())
+///     .with_language(Language::from_str(r#"{ "id": "en-US", "texts": {} }"#).unwrap())
+///     .with_language(Language::from_str(r#"{ "id": "en", "texts": {} }"#).unwrap())
+///     .with_fallback("en-US".parse().unwrap())
+///     .negotiate(&requested);
+/// ```
+pub struct I18nConfig {
+    pub(crate) selected_language: LanguageIdentifier,
+    pub(crate) fallback_languages: Vec<LanguageIdentifier>,
+    pub(crate) languages: Vec<Language>,
+    pub(crate) namespaces: HashMap<String, Vec<Language>>,
+    pub(crate) loader: Option<LanguageLoader>,
+    pub(crate) missing_policy: MissingPolicy,
+    pub(crate) pseudo_locales: bool,
+    /// Creates the signal of the selected language from the initial selection, see [`I18nConfig::with_persisted_language`].
+    pub(crate) selected_language_signal:
+        Option<Box<dyn FnOnce(LanguageIdentifier) -> Signal<LanguageIdentifier>>>,
+    /// The directory to reload languages from, see [`I18nConfig::with_hot_reload`].
+    #[cfg(all(feature = "i18n_hot_reload", not(target_family = "wasm")))]
+    pub(crate) hot_reload: Option<std::path::PathBuf>,
+}
+
+impl I18nConfig {
+    /// Creates a configuration with the given language selected.
+    pub fn new(selected_language: LanguageIdentifier) -> Self {
+        Self {
+            selected_language,
+            fallback_languages: Vec::new(),
+            languages: Vec::new(),
+            namespaces: HashMap::new(),
+            loader: None,
+            missing_policy: MissingPolicy::default(),
+            pseudo_locales: false,
+            selected_language_signal: None,
+            #[cfg(all(feature = "i18n_hot_reload", not(target_family = "wasm")))]
+            hot_reload: None,
+        }
+    }
+
+    /// Adds a language.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.languages.push(language);
+        self
+    }
+
+    /// Adds multiple languages.
+    pub fn with_languages(mut self, languages: impl IntoIterator<Item = Language>) -> Self {
+        self.languages.extend(languages);
+        self
+    }
+
+    /// Adds a language to fall back to when a key isn't found in the selected language.
+    ///
+    /// Fallback languages are tried in the order they are added.
+    pub fn with_fallback(mut self, fallback_language: LanguageIdentifier) -> Self {
+        self.fallback_languages.push(fallback_language);
+        self
+    }
+
+    /// Selects the added language that best matches the requested languages, see [`negotiate_language`].
+    ///
+    /// The selected language is kept if none of the requested languages match.
+    pub fn negotiate(mut self, requested: &[LanguageIdentifier]) -> Self {
+        let available = self
+            .languages
+            .iter()
+            .map(|language| language.id().clone())
+            .collect::<Vec<_>>();
+        if let Some(id) = negotiate_language(requested, &available) {
+            self.selected_language = id.clone();
+        }
+        self
+    }
 }
 
 pub fn use_init_i18n(
     selected_language: LanguageIdentifier,
     fallback_language: LanguageIdentifier,
     languages: impl FnOnce() -> Vec<Language>,
-) {
-    let selected_language = use_signal(|| selected_language);
-    let init_i18_data = use_signal(|| UseInitI18Data {
-        languages: languages(),
-        fallback_language,
+) -> UseI18 {
+    use_init_i18n_with_config(|| {
+        I18nConfig::new(selected_language)
+            .with_fallback(fallback_language)
+            .with_languages(languages())
+    })
+}
+
+/// Initializes i18n for this component and its children with the given configuration.
+pub fn use_init_i18n_with_config(init: impl FnOnce() -> I18nConfig) -> UseI18 {
+    let i18 = use_hook(|| {
+        let config = init();
+        let selected_language = match config.selected_language_signal {
+            Some(signal) => signal(config.selected_language),
+            None => Signal::new(config.selected_language),
+        };
+        let data = Signal::new(UseInitI18Data {
+            fallback_languages: config.fallback_languages,
+            languages: config.languages,
+            namespaces: config.namespaces,
+            loader: config.loader,
+            pending_loads: RefCell::default(),
+            missing_policy: config.missing_policy,
+            pseudo_locales: config.pseudo_locales,
+            missing: RefCell::default(),
+        });
+
+        provide_context(selected_language);
+        provide_context(data);
+
+        #[cfg(all(feature = "i18n_hot_reload", not(target_family = "wasm")))]
+        if let Some(path) = config.hot_reload {
+            super::hot_reload::watch_languages(data, path);
+        }
+
+        let i18 = provide_context(UseI18 {
+            selected_language,
+            data,
+            loading: Signal::new(None),
+        });
+        i18.load_language(&i18.selected_language.peek());
+        super::global::register(i18);
+        i18
     });
+    use_drop(move || super::global::unregister(i18));
+
+    // The language can also be selected without `set_language`, e.g. when it is synced from storage
+    use_effect(move || i18.load_language(&i18.selected_language.read()));
 
-    provide_context(selected_language);
-    provide_context(init_i18_data);
+    i18
 }
@@ -0,0 +1,180 @@
+//! Provides a hook to access the user's preferred languages.
+
+use dioxus::prelude::*;
+use unic_langid::LanguageIdentifier;
+
+/// A hook for receiving the user's preferred languages, most preferred first.
+///
+/// On web these are read from `navigator.languages` and updated whenever the browser fires a `languagechange` event.
+/// On desktop they are read from the `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables.
+///
+/// # Example
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::i18n::{use_init_i18n_with_config, use_system_locale, I18nConfig};
+///
+/// fn App() -> Element {
+///     let locales = use_system_locale();
+///     use_init_i18n_with_config(|| {
+///         I18nConfig::new("en-US".parse().unwrap()).negotiate(&locales.read())
+///     });
+///
+///     rsx! {
+///         p { "Preferred languages: {locales:?}" }
+///     }
+/// }
+/// ```
+pub fn use_system_locale() -> ReadOnlySignal<Vec<LanguageIdentifier>> {
+    let locales = use_signal(get_system_locale);
+
+    listen(locales);
+
+    use_hook(|| ReadOnlySignal::new(locales))
+}
+
+// Listener for the web implementation.
+#[cfg(target_family = "wasm")]
+fn listen(mut locales: Signal<Vec<LanguageIdentifier>>) {
+    use std::rc::Rc;
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let listener = use_hook(|| {
+        let on_language_change = Closure::wrap(Box::new(move || {
+            locales.set(get_system_locale());
+        }) as Box<dyn FnMut()>);
+
+        if let Some(window) = web_sys::window() {
+            window
+                .add_event_listener_with_callback(
+                    "languagechange",
+                    on_language_change.as_ref().unchecked_ref(),
+                )
+                .ok();
+        }
+        Rc::new(on_language_change)
+    });
+
+    use_drop(move || {
+        if let Some(window) = web_sys::window() {
+            window
+                .remove_event_listener_with_callback(
+                    "languagechange",
+                    listener.as_ref().as_ref().unchecked_ref(),
+                )
+                .ok();
+        }
+    });
+}
+
+// The environment doesn't change while the app is running, so there is nothing to listen to.
+#[cfg(not(target_family = "wasm"))]
+fn listen(_locales: Signal<Vec<LanguageIdentifier>>) {}
+
+/// Get the user's preferred languages, most preferred first.
+///
+/// Locales that can't be parsed are skipped.
+pub fn get_system_locale() -> Vec<LanguageIdentifier> {
+    let mut locales = Vec::new();
+    for locale in get_system_locale_platform() {
+        if let Some(locale) = parse_locale(&locale) {
+            if !locales.contains(&locale) {
+                locales.push(locale);
+            }
+        }
+    }
+    locales
+}
+
+// Web implementation of the locale getter.
+#[cfg(target_family = "wasm")]
+fn get_system_locale_platform() -> Vec<String> {
+    let Some(window) = web_sys::window() else {
+        return Vec::new();
+    };
+    window
+        .navigator()
+        .languages()
+        .iter()
+        .filter_map(|language| language.as_string())
+        .collect()
+}
+
+// Desktop implementation of the locale getter.
+#[cfg(not(target_family = "wasm"))]
+fn get_system_locale_platform() -> Vec<String> {
+    locales_from_env(|name| std::env::var(name).ok())
+}
+
+/// Reads the locales from the environment variables, in the order of POSIX precedence.
+#[cfg(not(target_family = "wasm"))]
+fn locales_from_env(var: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(var)
+        .collect()
+}
+
+/// Parses a BCP 47 language tag (`en-US`) or a POSIX locale (`en_US.UTF-8@euro`).
+///
+/// The `latin` and `cyrillic` modifiers of POSIX locales set the script (`sr_RS@latin` is `sr-Latn-RS`), other modifiers are ignored.
+fn parse_locale(locale: &str) -> Option<LanguageIdentifier> {
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale, None),
+    };
+    // Strip the POSIX codeset
+    let locale = locale.split('.').next()?.trim();
+    // The C and POSIX locales don't identify a language
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return None;
+    }
+    let mut id: LanguageIdentifier = locale.replace('_', "-").parse().ok()?;
+    let script = match modifier {
+        Some("latin") => Some("Latn"),
+        Some("cyrillic") => Some("Cyrl"),
+        _ => None,
+    };
+    if let Some(script) = script {
+        id.script = script.parse().ok();
+    }
+    Some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(locale: &str) -> Option<String> {
+        parse_locale(locale).map(|id| id.to_string())
+    }
+
+    #[test]
+    fn parses_posix_locales() {
+        assert_eq!(parse("de_DE.UTF-8").as_deref(), Some("de-DE"));
+        assert_eq!(parse("sr_RS@latin").as_deref(), Some("sr-Latn-RS"));
+        assert_eq!(parse("de_DE.UTF-8@euro").as_deref(), Some("de-DE"));
+        assert_eq!(parse("en-US").as_deref(), Some("en-US"));
+        assert_eq!(parse("C"), None);
+        assert_eq!(parse("C.UTF-8"), None);
+        assert_eq!(parse("POSIX"), None);
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[test]
+    fn reads_locales_in_posix_order() {
+        fn env(lc_all: Option<&'static str>) -> impl Fn(&str) -> Option<String> {
+            move |name| match name {
+                "LC_ALL" => lc_all.map(str::to_string),
+                "LC_MESSAGES" => Some("fr_FR.UTF-8".to_string()),
+                "LANG" => Some("de_DE.UTF-8".to_string()),
+                _ => None,
+            }
+        }
+        assert_eq!(
+            locales_from_env(env(Some("es_ES.UTF-8"))),
+            ["es_ES.UTF-8", "fr_FR.UTF-8", "de_DE.UTF-8"]
+        );
+        assert_eq!(locales_from_env(env(None)), ["fr_FR.UTF-8", "de_DE.UTF-8"]);
+    }
+}
@@ -64,10 +64,12 @@ impl StorageBacking for LocalStorage {
         if let Some(subscriptions) = SUBSCRIPTIONS.get() {
             let read_binding = subscriptions.read().unwrap();
             if let Some(subscription) = read_binding.get(&key_clone) {
-                subscription
+                if let Err(err) = subscription
                     .tx
                     .send(StorageChannelPayload::new(value_clone))
-                    .unwrap();
+                {
+                    tracing::trace!("No receivers left for \"{}\": {}", key_clone, err);
+                }
             }
         }
     }
@@ -87,20 +89,15 @@ impl StorageSubscriber<LocalStorage> for LocalStorage {
         // Initialize the subscriptions map if it hasn't been initialized yet.
         let subscriptions = SUBSCRIPTIONS.get_or_init(|| RwLock::new(HashMap::new()));
 
-        // Check if the subscription already exists. If it does, return the existing subscription's channel.
+        // Check if the subscription already exists. If it does, register another subscriber and return the existing subscription's channel.
         // If it doesn't, create a new subscription and return its channel.
-        let read_binding = subscriptions.read().unwrap();
-        match read_binding.get(key) {
-            Some(subscription) => subscription.tx.subscribe(),
+        let mut write_binding = subscriptions.write().unwrap();
+        match write_binding.get_mut(key) {
+            Some(subscription) => subscription.add_subscriber(),
             None => {
-                drop(read_binding);
                 let (tx, rx) = channel::<StorageChannelPayload>(StorageChannelPayload::default());
                 let subscription = StorageSubscription::new::<LocalStorage, T>(tx, key.clone());
-
-                subscriptions
-                    .write()
-                    .unwrap()
-                    .insert(key.clone(), subscription);
+                write_binding.insert(key.clone(), subscription);
                 rx
             }
         }
@@ -111,13 +108,14 @@ impl StorageSubscriber<LocalStorage> for LocalStorage {
 
         // Fail silently if unsubscribe is called but the subscriptions map isn't initialized yet.
         if let Some(subscriptions) = SUBSCRIPTIONS.get() {
-            let read_binding = subscriptions.read().unwrap();
-
-            // If the subscription exists, remove it from the subscriptions map.
-            if read_binding.contains_key(key) {
-                tracing::trace!("Found entry for \"{}\"", key);
-                drop(read_binding);
-                subscriptions.write().unwrap().remove(key);
+            let mut write_binding = subscriptions.write().unwrap();
+
+            // If this was the last subscriber, remove the subscription from the subscriptions map.
+            if let Some(subscription) = write_binding.get_mut(key) {
+                if subscription.remove_subscriber() {
+                    tracing::trace!("Removing subscription for \"{}\"", key);
+                    write_binding.remove(key);
+                }
             }
         }
     }
@@ -126,3 +124,33 @@ impl StorageSubscriber<LocalStorage> for LocalStorage {
 /// A map of all the channels that are currently subscribed to and the getters for the corresponding storage entry.
 /// This gets initialized lazily.
 static SUBSCRIPTIONS: OnceLock<RwLock<HashMap<String, StorageSubscription>>> = OnceLock::new();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscriber_count(key: &str) -> Option<usize> {
+        let subscriptions = SUBSCRIPTIONS.get()?.read().unwrap();
+        subscriptions
+            .get(key)
+            .map(|subscription| subscription.subscribers)
+    }
+
+    #[test]
+    fn subscriptions_are_reference_counted() {
+        let key = "fs_reference_counted".to_string();
+        let first = LocalStorage::subscribe::<i32>(&key);
+        let second = LocalStorage::subscribe::<i32>(&key);
+        assert_eq!(subscriber_count(&key), Some(2));
+
+        LocalStorage::unsubscribe(&key);
+        assert_eq!(subscriber_count(&key), Some(1));
+        assert!(first.has_changed().is_ok());
+
+        LocalStorage::unsubscribe(&key);
+        assert_eq!(subscriber_count(&key), None);
+        // With the sender gone, listening tasks see the channel as closed and exit.
+        assert!(first.has_changed().is_err());
+        assert!(second.has_changed().is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    cell::OnceCell,
     collections::HashMap,
     sync::{Arc, RwLock},
 };
@@ -8,22 +9,29 @@ use once_cell::sync::Lazy;
 use serde::{de::DeserializeOwned, Serialize};
 use tokio::sync::watch::{channel, Receiver};
 use wasm_bindgen::prelude::Closure;
-use wasm_bindgen::JsCast;
-use web_sys::{window, Storage};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{window, BroadcastChannel, MessageEvent, Storage};
 
 use crate::storage::{
     serde_to_string, try_serde_from_string, StorageBacking, StorageChannelPayload,
     StorageSubscriber, StorageSubscription,
 };
 
+/// The name of the broadcast channel used to sync session storage between windows of the same origin.
+const SESSION_CHANNEL_NAME: &str = "dioxus-sdk-session-storage";
+
+type Subscriptions = Arc<RwLock<HashMap<String, StorageSubscription>>>;
+
 #[derive(Clone)]
 pub struct LocalStorage;
 
 impl StorageBacking for LocalStorage {
     type Key = String;
 
-    fn set<T: Serialize + Send + Sync + 'static>(key: String, value: &T) {
-        set(key, value, WebStorageType::Local);
+    fn set<T: Serialize + Send + Sync + Clone + 'static>(key: String, value: &T) {
+        set(key.clone(), value, WebStorageType::Local);
+        // The storage event is only fired in other tabs, so subscribers in this tab are notified directly.
+        notify_subscribers(&SUBSCRIPTIONS, &key, value);
     }
 
     fn get<T: DeserializeOwned>(key: &String) -> Option<T> {
@@ -35,35 +43,16 @@ impl StorageSubscriber<LocalStorage> for LocalStorage {
     fn subscribe<T: DeserializeOwned + Send + Sync + Clone + 'static>(
         key: &String,
     ) -> Receiver<StorageChannelPayload> {
-        let read_binding = SUBSCRIPTIONS.read().unwrap();
-        match read_binding.get(key) {
-            Some(subscription) => subscription.tx.subscribe(),
-            None => {
-                drop(read_binding);
-                let (tx, rx) = channel::<StorageChannelPayload>(StorageChannelPayload::default());
-                let subscription = StorageSubscription::new::<LocalStorage, T>(tx, key.clone());
-                SUBSCRIPTIONS
-                    .write()
-                    .unwrap()
-                    .insert(key.clone(), subscription);
-                rx
-            }
-        }
+        subscribe::<LocalStorage, T>(&SUBSCRIPTIONS, key)
     }
 
     fn unsubscribe(key: &String) {
-        let read_binding = SUBSCRIPTIONS.read().unwrap();
-        if let Some(entry) = read_binding.get(key) {
-            if entry.tx.is_closed() {
-                drop(read_binding);
-                SUBSCRIPTIONS.write().unwrap().remove(key);
-            }
-        }
+        unsubscribe(&SUBSCRIPTIONS, key)
     }
 }
 
 /// A map of all the channels that are currently subscribed to and the getters for the corresponding storage entry. This gets initialized lazily and will set up a listener for storage events.
-static SUBSCRIPTIONS: Lazy<Arc<RwLock<HashMap<String, StorageSubscription>>>> = Lazy::new(|| {
+static SUBSCRIPTIONS: Lazy<Subscriptions> = Lazy::new(|| {
     // Create a closure that will be called when a storage event occurs.
     let closure = Closure::wrap(Box::new(move |e: web_sys::StorageEvent| {
         tracing::trace!("Storage event: {:?}", e);
@@ -99,8 +88,20 @@ pub struct SessionStorage;
 impl StorageBacking for SessionStorage {
     type Key = String;
 
-    fn set<T: Serialize + Send + Sync + 'static>(key: String, value: &T) {
-        set(key, value, WebStorageType::Session);
+    fn set<T: Serialize + Send + Sync + Clone + 'static>(key: String, value: &T) {
+        let as_str = set(key.clone(), value, WebStorageType::Session);
+        notify_subscribers(&SESSION_SUBSCRIPTIONS, &key, value);
+
+        // Session storage is scoped to a single window, so other windows are only told about the change when syncing was opted into.
+        SESSION_CHANNEL.with(|session_channel| {
+            if let Some(Some(session_channel)) = session_channel.get() {
+                let message =
+                    js_sys::Array::of2(&JsValue::from_str(&key), &JsValue::from_str(&as_str));
+                if let Err(err) = session_channel.post_message(&message) {
+                    tracing::error!("Error broadcasting session storage change: {:?}", err);
+                }
+            }
+        });
     }
 
     fn get<T: DeserializeOwned>(key: &String) -> Option<T> {
@@ -108,12 +109,124 @@ impl StorageBacking for SessionStorage {
     }
 }
 
-fn set<T: Serialize>(key: String, value: &T, storage_type: WebStorageType) {
+impl StorageSubscriber<SessionStorage> for SessionStorage {
+    fn subscribe<T: DeserializeOwned + Send + Sync + Clone + 'static>(
+        key: &String,
+    ) -> Receiver<StorageChannelPayload> {
+        subscribe::<SessionStorage, T>(&SESSION_SUBSCRIPTIONS, key)
+    }
+
+    fn unsubscribe(key: &String) {
+        unsubscribe(&SESSION_SUBSCRIPTIONS, key)
+    }
+}
+
+impl SessionStorage {
+    /// Syncs the session storage of this window with the other windows of the same origin that call this as well.
+    ///
+    /// Session storage belongs to a single tab, so by default only the subscribers in this tab are notified of changes.
+    /// Call this when the app starts if its windows should share their session state instead, for example windows the app opens itself.
+    pub fn sync_across_windows() {
+        SESSION_CHANNEL.with(|session_channel| {
+            session_channel.get_or_init(create_session_channel);
+        });
+    }
+}
+
+/// A map of all the channels that are currently subscribed to session storage keys and the getters for the corresponding storage entry.
+static SESSION_SUBSCRIPTIONS: Lazy<Subscriptions> =
+    Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
+
+thread_local! {
+    /// The broadcast channel used to sync session storage between windows. This is only opened by [`SessionStorage::sync_across_windows`] and will set up a listener for messages from other windows.
+    static SESSION_CHANNEL: OnceCell<Option<BroadcastChannel>> = const { OnceCell::new() };
+}
+
+/// Opens the session storage broadcast channel and applies the changes other windows send through it.
+fn create_session_channel() -> Option<BroadcastChannel> {
+    let session_channel = BroadcastChannel::new(SESSION_CHANNEL_NAME).ok()?;
+    // Create a closure that will be called when another window changes its session storage.
+    let closure = Closure::wrap(Box::new(move |e: MessageEvent| {
+        let Ok(message) = e.data().dyn_into::<js_sys::Array>() else {
+            return;
+        };
+        let (Some(key), Some(value)) = (message.get(0).as_string(), message.get(1).as_string())
+        else {
+            return;
+        };
+        tracing::trace!("Session storage message for \"{}\"", key);
+        // Write the raw value instead of going through `SessionStorage::set` so the change isn't broadcast back.
+        if let Some(storage) = get_storage_by_type(WebStorageType::Session) {
+            storage.set_item(&key, &value).ok();
+        }
+        if let Some(subscription) = SESSION_SUBSCRIPTIONS.read().unwrap().get(&key) {
+            match subscription.get_and_send() {
+                Ok(_) => tracing::trace!("Sent session storage event"),
+                Err(err) => {
+                    tracing::error!("Error sending session storage event: {:?}", err.to_string())
+                }
+            }
+        }
+    }) as Box<dyn FnMut(MessageEvent)>);
+    session_channel.set_onmessage(Some(closure.as_ref().unchecked_ref()));
+    // Relinquish ownership of the closure to the JS runtime so that it can be called later.
+    closure.forget();
+    Some(session_channel)
+}
+
+/// Subscribes to the given key in the subscriptions map, creating the subscription if it doesn't exist yet.
+fn subscribe<S, T>(subscriptions: &Subscriptions, key: &str) -> Receiver<StorageChannelPayload>
+where
+    S: StorageBacking<Key = String> + StorageSubscriber<S>,
+    T: DeserializeOwned + Send + Sync + Clone + 'static,
+{
+    let mut write_binding = subscriptions.write().unwrap();
+    match write_binding.get_mut(key) {
+        Some(subscription) => subscription.add_subscriber(),
+        None => {
+            let (tx, rx) = channel::<StorageChannelPayload>(StorageChannelPayload::default());
+            let subscription = StorageSubscription::new::<S, T>(tx, key.to_string());
+            write_binding.insert(key.to_string(), subscription);
+            rx
+        }
+    }
+}
+
+/// Unsubscribes from the given key in the subscriptions map, removing the subscription once it has no subscribers left.
+fn unsubscribe(subscriptions: &Subscriptions, key: &str) {
+    let mut write_binding = subscriptions.write().unwrap();
+    if let Some(subscription) = write_binding.get_mut(key) {
+        if subscription.remove_subscriber() {
+            tracing::trace!("Removing subscription for \"{}\"", key);
+            write_binding.remove(key);
+        }
+    }
+}
+
+/// Sends the new value to the subscribers of the given key in this window, if there are any.
+fn notify_subscribers<T: Send + Sync + Clone + 'static>(
+    subscriptions: &Subscriptions,
+    key: &str,
+    value: &T,
+) {
+    if let Some(subscription) = subscriptions.read().unwrap().get(key) {
+        if let Err(err) = subscription
+            .tx
+            .send(StorageChannelPayload::new(value.clone()))
+        {
+            tracing::trace!("No receivers left for \"{}\": {}", key, err);
+        }
+    }
+}
+
+/// Serializes the value and writes it to the given storage, returning the serialized value.
+fn set<T: Serialize>(key: String, value: &T, storage_type: WebStorageType) -> String {
     let as_str = serde_to_string(value);
     get_storage_by_type(storage_type)
         .unwrap()
         .set_item(&key, &as_str)
         .unwrap();
+    as_str
 }
 
 fn get<T: DeserializeOwned>(key: &str, storage_type: WebStorageType) -> Option<T> {
@@ -135,3 +248,51 @@ enum WebStorageType {
     Local,
     Session,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Waits for the messages posted to broadcast channels to be delivered.
+    async fn sleep(ms: i32) {
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            window()
+                .unwrap()
+                .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms)
+                .unwrap();
+        });
+        wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn session_storage_is_only_synced_across_windows_when_opted_in() {
+        // Another window is simulated by a second channel, which gets the messages this window posts
+        let messages = Rc::new(Cell::new(0));
+        let other_window = BroadcastChannel::new(SESSION_CHANNEL_NAME).unwrap();
+        let closure = Closure::wrap(Box::new({
+            let messages = messages.clone();
+            move |_: MessageEvent| messages.set(messages.get() + 1)
+        }) as Box<dyn FnMut(MessageEvent)>);
+        other_window.set_onmessage(Some(closure.as_ref().unchecked_ref()));
+
+        let mut receiver = SessionStorage::subscribe::<i32>(&"count".to_string());
+        SessionStorage::set("count".to_string(), &1);
+        sleep(50).await;
+        // Subscribers in this tab are notified, other windows aren't
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(receiver.borrow_and_update().data::<i32>(), Some(&1));
+        assert_eq!(messages.get(), 0);
+
+        SessionStorage::sync_across_windows();
+        SessionStorage::set("count".to_string(), &2);
+        sleep(50).await;
+        assert_eq!(messages.get(), 1);
+
+        SessionStorage::unsubscribe(&"count".to_string());
+        other_window.close();
+    }
+}
@@ -37,7 +37,9 @@ use postcard::to_allocvec;
 use serde::{de::DeserializeOwned, Serialize};
 use std::any::Any;
 use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 use std::sync::Arc;
 use tokio::sync::watch::error::SendError;
 use tokio::sync::watch::{Receiver, Sender};
@@ -164,12 +166,14 @@ where
                 storage_entry.save_to_storage_on_change();
                 storage_entry.subscribe_to_storage();
             }
+            storage_entry.keep_subscription();
             *storage_entry.data()
         } else {
             // The client is rendered normally, so we can just use the synced storage entry.
             let storage_entry = new_synced_storage_entry::<S, T>(key, init.take().unwrap());
             storage_entry.save_to_storage_on_change();
             storage_entry.subscribe_to_storage();
+            storage_entry.keep_subscription();
             *storage_entry.data()
         }
     };
@@ -281,6 +285,130 @@ pub trait StorageEntryTrait<S: StorageBacking, T: PartialEq + Clone + 'static>:
             }
         });
     }
+
+    /// Creates a read-only signal derived from a part of the state.
+    ///
+    /// Readers of the returned signal are only notified when the selected part changes, not whenever anything in the state changes.
+    /// This spawns a task in the current scope, so call it once (for example inside `use_hook`) rather than on every render.
+    ///
+    /// ## Usage
+    ///
+    /// ```rust
+    /// use dioxus_sdk::storage::{use_storage_entry, LocalStorage, StorageEntryTrait};
+    /// use dioxus::prelude::*;
+    ///
+    /// // The settings are stored as (theme, font size)
+    /// type AppSettings = (String, u32);
+    ///
+    /// fn app() -> Element {
+    ///     let settings = use_storage_entry::<LocalStorage, AppSettings>("settings".to_string(), Default::default);
+    ///     let theme = use_hook(|| settings.map(|settings| &settings.0));
+    ///     rsx! { "{theme}" }
+    /// }
+    /// ```
+    fn map<U: Clone + PartialEq + 'static>(
+        &self,
+        select: impl Fn(&T) -> &U + 'static,
+    ) -> ReadOnlySignal<U> {
+        let data = *self.data();
+        let mut slice = Signal::new(select(&data.peek()).clone());
+        spawn(async move {
+            loop {
+                let (rc, mut reactive_context) = ReactiveContext::new();
+                rc.run_in(|| {
+                    let data = data.read();
+                    let selected = select(&data);
+                    if *slice.peek() != *selected {
+                        *slice.write() = selected.clone();
+                    }
+                });
+                if reactive_context.next().await.is_none() {
+                    break;
+                }
+            }
+        });
+        ReadOnlySignal::new(slice)
+    }
+
+    /// Creates a writable signal derived from a part of the state.
+    ///
+    /// Readers of the returned signal are only notified when the selected part changes. Writes to the returned signal are written back into the state, which is then saved like any other change to the entry.
+    /// The write back is deferred: it's done by a task of the current scope, so the state only has the new value once the tasks of the app have run, not right after the write.
+    /// This spawns tasks in the current scope, so call it once (for example inside `use_hook`) rather than on every render.
+    ///
+    /// ## Usage
+    ///
+    /// ```rust
+    /// use dioxus_sdk::storage::{use_storage_entry, LocalStorage, StorageEntryTrait};
+    /// use dioxus::prelude::*;
+    ///
+    /// // The settings are stored as (theme, font size)
+    /// type AppSettings = (String, u32);
+    ///
+    /// fn app() -> Element {
+    ///     let settings = use_storage_entry::<LocalStorage, AppSettings>("settings".to_string(), Default::default);
+    ///     let mut font_size = use_hook(|| {
+    ///         settings.lens(|settings| &settings.1, |settings| &mut settings.1)
+    ///     });
+    ///     rsx! {
+    ///         button { onclick: move |_| font_size += 1, "Font size: {font_size}" }
+    ///     }
+    /// }
+    /// ```
+    fn lens<U: Clone + PartialEq + 'static>(
+        &self,
+        select: impl Fn(&T) -> &U + 'static,
+        select_mut: impl Fn(&mut T) -> &mut U + 'static,
+    ) -> Signal<U> {
+        let select = Rc::new(select);
+        let mut data = *self.data();
+        let mut slice = Signal::new(select(&data.peek()).clone());
+
+        // Copy changes to the selected part of the state into the derived signal.
+        // Only changes of the state since the last copy are copied, so a write to the derived signal that the other task hasn't written back yet isn't overwritten.
+        let select_clone = select.clone();
+        let mut last_selected = slice.peek().clone();
+        spawn(async move {
+            loop {
+                let (rc, mut reactive_context) = ReactiveContext::new();
+                rc.run_in(|| {
+                    let data = data.read();
+                    let selected = select_clone(&data);
+                    if *selected != last_selected {
+                        last_selected = selected.clone();
+                        if *slice.peek() != *selected {
+                            *slice.write() = selected.clone();
+                        }
+                    }
+                });
+                if reactive_context.next().await.is_none() {
+                    break;
+                }
+            }
+        });
+
+        // Write changes to the derived signal back into the state, likewise only those since the last write back
+        let mut last_value = slice.peek().clone();
+        spawn(async move {
+            loop {
+                let (rc, mut reactive_context) = ReactiveContext::new();
+                rc.run_in(|| {
+                    let value = slice.read();
+                    if *value != last_value {
+                        last_value = value.clone();
+                        if *select(&data.peek()) != *value {
+                            *select_mut(&mut data.write()) = value.clone();
+                        }
+                    }
+                });
+                if reactive_context.next().await.is_none() {
+                    break;
+                }
+            }
+        });
+
+        slice
+    }
 }
 
 /// A wrapper around StorageEntry that provides a channel to subscribe to updates to the underlying storage.
@@ -293,6 +421,8 @@ pub struct SyncedStorageEntry<
     pub(crate) entry: StorageEntry<S, T>,
     /// The channel to subscribe to updates to the underlying storage
     pub(crate) channel: Receiver<StorageChannelPayload>,
+    /// Unsubscribes from the underlying storage once the last clone of this entry is dropped
+    pub(crate) subscription: Rc<SubscriptionGuard<S>>,
 }
 
 impl<S, T> SyncedStorageEntry<S, T>
@@ -303,6 +433,7 @@ where
     pub fn new(key: S::Key, data: T) -> Self {
         let channel = S::subscribe::<T>(&key);
         Self {
+            subscription: Rc::new(SubscriptionGuard::new(key.clone())),
             entry: StorageEntry::new(key, data),
             channel,
         }
@@ -313,24 +444,36 @@ where
         &self.channel
     }
 
+    /// Keeps the subscription to the underlying storage for as long as the current scope, which also owns the signal of the entry.
+    ///
+    /// Only the entry itself and the task of [`SyncedStorageEntry::subscribe_to_storage`] hold the subscription otherwise, so it would be released while the signal is still in use once they are dropped.
+    pub(crate) fn keep_subscription(&self) {
+        CopyValue::new(self.subscription.clone());
+    }
+
     /// Creates a hook that will update the state when the underlying storage changes
     pub fn subscribe_to_storage(&self) {
         let storage_entry_signal = *self.data();
         let channel = self.channel.clone();
+        let subscription = self.subscription.clone();
         spawn(async move {
             to_owned![channel, storage_entry_signal];
-            loop {
-                // Wait for an update to the channel
-                if channel.changed().await.is_ok() {
-                    // Retrieve the latest value from the channel, mark it as read, and update the state
-                    let payload = channel.borrow_and_update();
-                    *storage_entry_signal.write() = payload
-                        .data
-                        .downcast_ref::<T>()
-                        .expect("Type mismatch with storage entry")
-                        .clone();
+            // Keep the subscription alive for as long as this task is running
+            let _subscription = subscription;
+            // Wait for an update to the channel. This errors once the sender has been dropped, meaning there is nothing left to listen to.
+            while channel.changed().await.is_ok() {
+                // Retrieve the latest value from the channel, mark it as read, and update the state
+                let payload = channel.borrow_and_update();
+                if let Some(data) = payload.data.downcast_ref::<T>() {
+                    // Changes made by this entry are sent back to it as well, so skip values we already have.
+                    if *storage_entry_signal.peek() != *data {
+                        *storage_entry_signal.write() = data.clone();
+                    }
+                } else {
+                    tracing::error!("Type mismatch with storage entry");
                 }
             }
+            tracing::trace!("Storage channel closed, stopping subscription task");
         });
     }
 }
@@ -464,13 +607,40 @@ pub trait StorageBacking: Clone + 'static {
 /// A trait for a subscriber to events from a storage backing
 pub trait StorageSubscriber<S: StorageBacking> {
     /// Subscribes to events from a storage backing for the given key
+    ///
+    /// Subscriptions are reference counted per key: every call to `subscribe` must be paired with a call to `unsubscribe`.
     fn subscribe<T: DeserializeOwned + Send + Sync + Clone + 'static>(
         key: &S::Key,
     ) -> Receiver<StorageChannelPayload>;
     /// Unsubscribes from events from a storage backing for the given key
+    ///
+    /// The subscription for the key is removed once the last subscriber unsubscribes.
     fn unsubscribe(key: &S::Key);
 }
 
+/// Calls [`StorageSubscriber::unsubscribe`] for its key when dropped.
+///
+/// Every [`SyncedStorageEntry`] holds one of these behind an `Rc`, so the subscription is released when the scope that owns the entry (and the tasks it spawned) is dropped.
+pub struct SubscriptionGuard<S: StorageBacking + StorageSubscriber<S>> {
+    key: S::Key,
+    _marker: PhantomData<S>,
+}
+
+impl<S: StorageBacking + StorageSubscriber<S>> SubscriptionGuard<S> {
+    pub(crate) fn new(key: S::Key) -> Self {
+        Self {
+            key,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S: StorageBacking + StorageSubscriber<S>> Drop for SubscriptionGuard<S> {
+    fn drop(&mut self) {
+        S::unsubscribe(&self.key);
+    }
+}
+
 /// A struct to hold information about processing a storage event.
 pub struct StorageSubscription {
     /// A getter function that will get the data from storage and return it as a StorageChannelPayload.
@@ -478,6 +648,9 @@ pub struct StorageSubscription {
 
     /// The channel to send the data to.
     pub(crate) tx: Arc<Sender<StorageChannelPayload>>,
+
+    /// The number of entries currently subscribed to this key. The subscription is removed once this reaches zero.
+    pub(crate) subscribers: usize,
 }
 
 impl StorageSubscription {
@@ -495,9 +668,22 @@ impl StorageSubscription {
         Self {
             getter: Box::new(getter),
             tx: Arc::new(tx),
+            subscribers: 1,
         }
     }
 
+    /// Registers another subscriber and returns a new receiver for the channel.
+    pub(crate) fn add_subscriber(&mut self) -> Receiver<StorageChannelPayload> {
+        self.subscribers += 1;
+        self.tx.subscribe()
+    }
+
+    /// Removes a subscriber and returns true if there are no subscribers left.
+    pub(crate) fn remove_subscriber(&mut self) -> bool {
+        self.subscribers = self.subscribers.saturating_sub(1);
+        self.subscribers == 0
+    }
+
     /// Gets the latest data from storage and sends it to the channel.
     pub fn get_and_send(&self) -> Result<(), SendError<StorageChannelPayload>> {
         let payload = (self.getter)();
@@ -579,3 +765,184 @@ pub(crate) fn try_serde_from_string<T: DeserializeOwned>(value: &str) -> Option<
         Err(err) => None,
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use dioxus::dioxus_core::NoOpMutations;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use tokio::sync::watch::channel;
+
+    thread_local! {
+        static VALUES: RefCell<HashMap<String, Arc<dyn Any>>> = RefCell::new(HashMap::new());
+        static SUBSCRIPTIONS: RefCell<HashMap<String, StorageSubscription>> = RefCell::new(HashMap::new());
+        static SHOW_CHILDREN: Cell<bool> = const { Cell::new(true) };
+    }
+
+    /// An in-memory storage backing that keeps its values and subscriptions per thread so tests don't interfere with each other.
+    #[derive(Clone)]
+    pub(crate) struct TestStorage;
+
+    impl StorageBacking for TestStorage {
+        type Key = String;
+
+        fn get<T: DeserializeOwned + Clone + 'static>(key: &String) -> Option<T> {
+            VALUES.with(|values| values.borrow().get(key)?.downcast_ref::<T>().cloned())
+        }
+
+        fn set<T: Serialize + Send + Sync + Clone + 'static>(key: String, value: &T) {
+            VALUES.with(|values| values.borrow_mut().insert(key, Arc::new(value.clone())));
+        }
+    }
+
+    impl StorageSubscriber<TestStorage> for TestStorage {
+        fn subscribe<T: DeserializeOwned + Send + Sync + Clone + 'static>(
+            key: &String,
+        ) -> Receiver<StorageChannelPayload> {
+            SUBSCRIPTIONS.with(|subscriptions| {
+                let mut subscriptions = subscriptions.borrow_mut();
+                match subscriptions.get_mut(key) {
+                    Some(subscription) => subscription.add_subscriber(),
+                    None => {
+                        let (tx, rx) = channel(StorageChannelPayload::default());
+                        subscriptions.insert(
+                            key.clone(),
+                            StorageSubscription::new::<TestStorage, T>(tx, key.clone()),
+                        );
+                        rx
+                    }
+                }
+            })
+        }
+
+        fn unsubscribe(key: &String) {
+            SUBSCRIPTIONS.with(|subscriptions| {
+                let mut subscriptions = subscriptions.borrow_mut();
+                if let Some(subscription) = subscriptions.get_mut(key) {
+                    if subscription.remove_subscriber() {
+                        subscriptions.remove(key);
+                    }
+                }
+            })
+        }
+    }
+
+    fn subscriber_count(key: &str) -> Option<usize> {
+        SUBSCRIPTIONS.with(|subscriptions| subscriptions.borrow().get(key).map(|s| s.subscribers))
+    }
+
+    fn app() -> Element {
+        if SHOW_CHILDREN.with(Cell::get) {
+            rsx! {
+                Child {}
+                Child {}
+            }
+        } else {
+            None
+        }
+    }
+
+    #[allow(non_snake_case)]
+    fn Child() -> Element {
+        let count = use_synced_storage::<TestStorage, i32>("count".to_string(), || 0);
+        rsx! { "{count}" }
+    }
+
+    type Settings = (String, u32);
+    /// The settings entry and the lens of its font size.
+    type LensedSettings = (StorageEntry<TestStorage, Settings>, Signal<u32>);
+
+    thread_local! {
+        static SETTINGS: RefCell<Option<LensedSettings>> = const { RefCell::new(None) };
+        static THEME_RENDERS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn settings_app() -> Element {
+        let settings = use_storage_entry::<TestStorage, Settings>("settings".to_string(), || {
+            ("light".to_string(), 12)
+        });
+        let theme = use_hook(|| settings.map(|settings| &settings.0));
+        let font_size = use_hook(|| {
+            settings.save_to_storage_on_change();
+            settings.lens(|settings| &settings.1, |settings| &mut settings.1)
+        });
+        use_hook(|| {
+            SETTINGS.with(|stored| *stored.borrow_mut() = Some((settings.clone(), font_size)))
+        });
+        rsx! {
+            Theme { theme }
+        }
+    }
+
+    #[component]
+    fn Theme(theme: ReadOnlySignal<String>) -> Element {
+        THEME_RENDERS.set(THEME_RENDERS.get() + 1);
+        rsx! { "{theme}" }
+    }
+
+    /// Runs the tasks of the app, which copy changes between the entry and its derived signals.
+    fn run_tasks(dom: &mut VirtualDom) {
+        for _ in 0..5 {
+            dom.process_events();
+            dom.render_immediate(&mut NoOpMutations);
+        }
+    }
+
+    #[test]
+    fn lenses_write_back_to_the_entry() {
+        let mut dom = VirtualDom::new(settings_app);
+        dom.rebuild_in_place();
+        let (settings, mut font_size) =
+            SETTINGS.with(|settings| settings.borrow().clone().unwrap());
+        let stored = || TestStorage::get::<Settings>(&"settings".to_string()).unwrap();
+        assert_eq!(THEME_RENDERS.get(), 1);
+
+        // Writes through the lens reach the entry and storage once the tasks ran
+        dom.in_runtime(|| ScopeId::ROOT.in_runtime(|| font_size.set(14)));
+        assert_eq!(settings.peek().1, 12);
+        run_tasks(&mut dom);
+        assert_eq!(settings.peek().1, 14);
+        assert_eq!(stored().1, 14);
+        // The theme didn't change, so its readers weren't notified
+        assert_eq!(THEME_RENDERS.get(), 1);
+
+        let mut data = *settings.data();
+        dom.in_runtime(|| ScopeId::ROOT.in_runtime(|| data.write().0 = "dark".to_string()));
+        run_tasks(&mut dom);
+        assert_eq!(THEME_RENDERS.get(), 2);
+        assert_eq!(stored(), ("dark".to_string(), 14));
+        assert_eq!(*font_size.peek(), 14);
+    }
+
+    fn kept_subscription_app() -> Element {
+        use_hook(|| {
+            // Without a task listening to it, only the scope keeps the subscription
+            let entry = new_synced_storage_entry::<TestStorage, i32>("kept".to_string(), || 0);
+            entry.keep_subscription();
+        });
+        rsx! {}
+    }
+
+    #[test]
+    fn scope_keeps_the_subscription() {
+        let mut dom = VirtualDom::new(kept_subscription_app);
+        dom.rebuild_in_place();
+        assert_eq!(subscriber_count("kept"), Some(1));
+
+        drop(dom);
+        assert_eq!(subscriber_count("kept"), None);
+    }
+
+    #[test]
+    fn synced_storage_unsubscribes_on_unmount() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        assert_eq!(subscriber_count("count"), Some(2));
+
+        SHOW_CHILDREN.with(|show| show.set(false));
+        dom.mark_dirty(ScopeId::ROOT);
+        dom.render_immediate(&mut NoOpMutations);
+        assert_eq!(subscriber_count("count"), None);
+    }
+}
@@ -2,6 +2,22 @@
 
 mod use_channel;
 mod use_listen_channel;
+mod use_named_channel;
 
 pub use use_channel::*;
 pub use use_listen_channel::*;
+pub use use_named_channel::*;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "channel_shared")] {
+        mod use_shared_channel;
+        pub use use_shared_channel::*;
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "channel_rpc")] {
+        mod use_rpc_channel;
+        pub use use_rpc_channel::*;
+    }
+}
@@ -1,13 +1,81 @@
 use async_broadcast::{broadcast, InactiveReceiver, Receiver, SendError, Sender, TrySendError};
 use dioxus::prelude::*;
+use std::rc::Rc;
 use uuid::Uuid;
 
+/// Forwards the messages sent on a channel to other windows or tabs, see [`super::use_shared_channel()`].
+pub(crate) type Forward<MessageType> = Rc<dyn Fn(&MessageType)>;
+
+/// What happens when a message is sent to a channel that is full, because its slowest listener hasn't received the older messages yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// [`UseChannel::send`] waits until there is room for the message, and [`UseChannel::try_send`] fails with [`TrySendError::Full`].
+    #[default]
+    Block,
+    /// Both [`UseChannel::send`] and [`UseChannel::try_send`] fail right away.
+    Reject,
+    /// The oldest message is dropped to make room for the new one.
+    ///
+    /// Listeners that didn't receive the dropped messages get [`UseListenChannelError::Overflowed`](super::UseListenChannelError::Overflowed)
+    /// with how many messages they missed, before they receive the next one.
+    DropOldest,
+}
+
+/// The configuration of a channel, see [`use_channel_with_config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelConfig {
+    capacity: usize,
+    overflow: OverflowMode,
+    await_active: bool,
+}
+
+impl ChannelConfig {
+    /// Creates a configuration for a channel that holds up to `capacity` messages.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            overflow: OverflowMode::default(),
+            await_active: true,
+        }
+    }
+
+    /// Sets what happens when a message is sent while the channel is full.
+    pub fn with_overflow(mut self, overflow: OverflowMode) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Sets whether [`UseChannel::send`] waits for a listener when there is none, which is the default.
+    ///
+    /// Otherwise the message is returned in a [`SendError`], like [`UseChannel::try_send`] does with [`TrySendError::Inactive`].
+    pub fn with_await_active(mut self, await_active: bool) -> Self {
+        self.await_active = await_active;
+        self
+    }
+
+    pub(crate) fn overflow(&self) -> OverflowMode {
+        self.overflow
+    }
+
+    /// Creates the sender and receiver of the channel.
+    pub(crate) fn broadcast<MessageType: Clone>(
+        &self,
+    ) -> (Sender<MessageType>, Receiver<MessageType>) {
+        let (mut sender, receiver) = broadcast::<MessageType>(self.capacity);
+        sender.set_overflow(self.overflow == OverflowMode::DropOldest);
+        sender.set_await_active(self.await_active);
+        (sender, receiver)
+    }
+}
+
 /// Send and listen for messages between multiple components.
 #[derive(Clone, Copy)]
 pub struct UseChannel<MessageType: Clone + 'static> {
     id: Uuid,
+    overflow: OverflowMode,
     sender: Signal<Sender<MessageType>>,
     inactive_receiver: Signal<InactiveReceiver<MessageType>>,
+    forward: Signal<Option<Forward<MessageType>>>,
 }
 
 impl<T: Clone> PartialEq for UseChannel<T> {
@@ -17,14 +85,89 @@ impl<T: Clone> PartialEq for UseChannel<T> {
 }
 
 impl<MessageType: Clone + 'static> UseChannel<MessageType> {
+    /// Creates a handle to a channel for the current component.
+    pub(crate) fn from_parts(
+        id: Uuid,
+        overflow: OverflowMode,
+        sender: Sender<MessageType>,
+        inactive_receiver: InactiveReceiver<MessageType>,
+        forward: Option<Forward<MessageType>>,
+    ) -> Self {
+        Self {
+            id,
+            overflow,
+            sender: Signal::new(sender),
+            inactive_receiver: Signal::new(inactive_receiver),
+            forward: Signal::new(forward),
+        }
+    }
+
     /// Tries to send a message to all listeners of the channel.
+    ///
+    /// A [shared channel](super::use_shared_channel()) only sends the message to other windows or tabs when this channel isn't full or closed.
     pub fn try_send(&self, msg: impl Into<MessageType>) -> Result<(), TrySendError<MessageType>> {
-        self.sender.peek().try_broadcast(msg.into()).map(|_| ())
+        let msg = msg.into();
+        let forward = self.forward.peek().clone();
+        let forwarded = forward.as_ref().map(|_| msg.clone());
+        let result = self.sender.peek().try_broadcast(msg).map(|_| ());
+        // Other windows or tabs may have listeners even when this one has none
+        if matches!(result, Ok(()) | Err(TrySendError::Inactive(_))) {
+            if let (Some(forward), Some(msg)) = (forward, forwarded) {
+                forward(&msg);
+            }
+        }
+        result
     }
 
     /// Sends a message to all listeners of the channel.
+    ///
+    /// When the channel is full this waits for room, unless its [`OverflowMode`] is [`OverflowMode::Reject`].
     pub async fn send(&self, msg: impl Into<MessageType>) -> Result<(), SendError<MessageType>> {
-        self.sender.peek().broadcast(msg.into()).await.map(|_| ())
+        let msg = msg.into();
+        let sender = self.sender.peek().clone();
+        if self.overflow == OverflowMode::Reject && sender.is_full() {
+            return Err(SendError(msg));
+        }
+        let forward = self.forward.peek().clone();
+        let forwarded = forward.as_ref().map(|_| msg.clone());
+        let result = sender.broadcast(msg).await.map(|_| ());
+        // The message is only rejected when the channel is closed, not when this window has no listeners
+        if result.is_ok() || !sender.is_closed() {
+            if let (Some(forward), Some(msg)) = (forward, forwarded) {
+                forward(&msg);
+            }
+        }
+        result
+    }
+
+    /// What happens when a message is sent while the channel is full.
+    pub fn overflow(&self) -> OverflowMode {
+        self.overflow
+    }
+
+    /// How many messages the channel holds.
+    pub fn capacity(&self) -> usize {
+        self.sender.peek().capacity()
+    }
+
+    /// How many messages the slowest listener hasn't received yet.
+    pub fn len(&self) -> usize {
+        self.sender.peek().len()
+    }
+
+    /// Whether every listener received every message.
+    pub fn is_empty(&self) -> bool {
+        self.sender.peek().is_empty()
+    }
+
+    /// Whether the channel is full, so sending a message blocks, fails or drops the oldest message, see [`OverflowMode`].
+    pub fn is_full(&self) -> bool {
+        self.sender.peek().is_full()
+    }
+
+    /// How many listeners the channel has.
+    pub fn listener_count(&self) -> usize {
+        self.sender.peek().receiver_count()
     }
 
     /// Create a receiver for the channel.
@@ -35,14 +178,75 @@ impl<MessageType: Clone + 'static> UseChannel<MessageType> {
 }
 
 /// Send and listen for messages between multiple components.
+///
+/// The channel holds up to `size` messages, see [`use_channel_with_config`] to choose what happens when it's full.
 pub fn use_channel<MessageType: Clone + 'static>(size: usize) -> UseChannel<MessageType> {
+    use_channel_with_config(ChannelConfig::new(size))
+}
+
+/// Send and listen for messages between multiple components, with a configured channel.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::utils::channel::{
+///     use_channel_with_config, use_listen_channel, ChannelConfig, OverflowMode, UseListenChannelError,
+/// };
+///
+/// fn App() -> Element {
+///     // Only the latest positions matter, so slow listeners skip the old ones
+///     let channel = use_channel_with_config::<(f64, f64)>(
+///         ChannelConfig::new(8).with_overflow(OverflowMode::DropOldest),
+///     );
+///     use_listen_channel(&channel, move |message| async move {
+///         match message {
+///             Ok((x, y)) => println!("Moved to {x}, {y}"),
+///             Err(UseListenChannelError::Overflowed(missed)) => println!("Skipped {missed} positions"),
+///             Err(UseListenChannelError::Closed) => {}
+///         }
+///     });
+///
+///     rsx! {
+///         div {
+///             onmousemove: move |event| {
+///                 let point = event.client_coordinates();
+///                 channel.try_send((point.x, point.y)).ok();
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn use_channel_with_config<MessageType: Clone + 'static>(
+    config: ChannelConfig,
+) -> UseChannel<MessageType> {
     use_hook(|| {
-        let id = Uuid::new_v4();
-        let (sender, receiver) = broadcast::<MessageType>(size);
-        UseChannel {
-            id,
-            sender: Signal::new(sender),
-            inactive_receiver: Signal::new(receiver.deactivate()),
-        }
+        let (sender, receiver) = config.broadcast::<MessageType>();
+        UseChannel::from_parts(
+            Uuid::new_v4(),
+            config.overflow,
+            sender,
+            receiver.deactivate(),
+            None,
+        )
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_broadcast::TryRecvError;
+
+    #[test]
+    fn drops_oldest_messages() {
+        let config = ChannelConfig::new(2).with_overflow(OverflowMode::DropOldest);
+        let (sender, mut receiver) = config.broadcast::<u32>();
+        for message in 0..5 {
+            sender.try_broadcast(message).unwrap();
+        }
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Overflowed(3)));
+        assert_eq!(receiver.try_recv(), Ok(3));
+
+        let (sender, _receiver) = ChannelConfig::new(1).broadcast::<u32>();
+        sender.try_broadcast(0).unwrap();
+        assert!(sender.try_broadcast(1).unwrap_err().is_full());
+    }
+}
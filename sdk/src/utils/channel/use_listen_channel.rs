@@ -8,6 +8,9 @@ use super::UseChannel;
 pub type UseListenChannelError = RecvError;
 
 /// Create a messages listener for the given channel.
+///
+/// When the listener falls behind a channel that drops its oldest messages (see [`OverflowMode::DropOldest`](super::OverflowMode::DropOldest)),
+/// it gets [`UseListenChannelError::Overflowed`] with how many messages it missed before the next message.
 pub fn use_listen_channel<MessageType: Clone + 'static, Handler>(
     channel: &UseChannel<MessageType>,
     action: impl Fn(Result<MessageType, UseListenChannelError>) -> Handler + 'static,
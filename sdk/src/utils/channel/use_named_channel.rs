@@ -0,0 +1,212 @@
+use async_broadcast::{InactiveReceiver, Sender};
+use dioxus::prelude::*;
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+use uuid::Uuid;
+
+use super::use_channel::Forward;
+use super::{ChannelConfig, OverflowMode, UseChannel};
+
+/// How many messages a named channel holds by default, see [`use_named_channel`].
+pub const NAMED_CHANNEL_SIZE: usize = 16;
+
+/// The named channels of the app, provided at the root.
+///
+/// Channels are found by their name, message type and whether they are shared.
+#[derive(Clone, Default)]
+struct ChannelRegistry(Rc<RefCell<HashMap<ChannelKey, NamedChannel>>>);
+
+type ChannelKey = (String, TypeId, bool);
+
+/// Connects a channel to other windows or tabs, returning how to forward its messages to them.
+pub(crate) type Connect<MessageType> = fn(&str, Sender<MessageType>) -> Forward<MessageType>;
+
+struct NamedChannel {
+    id: Uuid,
+    /// The overflow mode, sender, inactive receiver and forwarding of the channel.
+    parts: Box<dyn Any>,
+    /// How many components use the channel.
+    users: usize,
+}
+
+/// Send and listen for messages between any components, using a channel found by its name.
+///
+/// Every component that uses the same name and message type gets the same channel, so it doesn't have to be passed around.
+/// The channel is created for the first component that uses it and removed when the last one unmounts.
+/// It holds [`NAMED_CHANNEL_SIZE`] messages, see [`use_named_channel_with_config`] to configure it.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::utils::channel::{use_listen_channel, use_named_channel};
+///
+/// #[derive(Clone)]
+/// enum CartEvent {
+///     Added(String),
+/// }
+///
+/// #[component]
+/// fn AddToCart(item: String) -> Element {
+///     let channel = use_named_channel::<CartEvent>("cart-events");
+///     rsx! {
+///         button {
+///             onclick: move |_| { channel.try_send(CartEvent::Added(item.clone())).ok(); },
+///             "Add to cart"
+///         }
+///     }
+/// }
+///
+/// #[component]
+/// fn CartCount() -> Element {
+///     let mut count = use_signal(|| 0);
+///     let channel = use_named_channel::<CartEvent>("cart-events");
+///     use_listen_channel(&channel, move |message| async move {
+///         if let Ok(CartEvent::Added(_)) = message {
+///             count += 1;
+///         }
+///     });
+///     rsx! { "{count} items" }
+/// }
+/// ```
+pub fn use_named_channel<MessageType: Clone + 'static>(name: &str) -> UseChannel<MessageType> {
+    use_named_channel_with_config(name, ChannelConfig::new(NAMED_CHANNEL_SIZE))
+}
+
+/// Like [`use_named_channel`], with a configured channel.
+///
+/// The configuration is used by the component that creates the channel, so components that use the same name should use the same configuration.
+pub fn use_named_channel_with_config<MessageType: Clone + 'static>(
+    name: &str,
+    config: ChannelConfig,
+) -> UseChannel<MessageType> {
+    use_registered_channel(name, config, None)
+}
+
+/// Gets the named channel from the registry, creating it with the configuration and connecting it to other windows or tabs
+/// when it's created if `connect` is given.
+pub(crate) fn use_registered_channel<MessageType: Clone + 'static>(
+    name: &str,
+    config: ChannelConfig,
+    connect: Option<Connect<MessageType>>,
+) -> UseChannel<MessageType> {
+    type Parts<MessageType> = (
+        OverflowMode,
+        Sender<MessageType>,
+        InactiveReceiver<MessageType>,
+        Option<Forward<MessageType>>,
+    );
+
+    let (registry, key, channel) = use_hook(|| {
+        let registry = try_consume_context::<ChannelRegistry>()
+            .unwrap_or_else(|| provide_root_context(ChannelRegistry::default()));
+        let key = (
+            name.to_string(),
+            TypeId::of::<MessageType>(),
+            connect.is_some(),
+        );
+
+        let mut channels = registry.0.borrow_mut();
+        let named = channels.entry(key.clone()).or_insert_with(|| {
+            let (sender, receiver) = config.broadcast::<MessageType>();
+            let forward = connect.map(|connect| connect(name, sender.clone()));
+            let parts: Parts<MessageType> =
+                (config.overflow(), sender, receiver.deactivate(), forward);
+            NamedChannel {
+                id: Uuid::new_v4(),
+                parts: Box::new(parts),
+                users: 0,
+            }
+        });
+        named.users += 1;
+        let (overflow, sender, inactive_receiver, forward) = named
+            .parts
+            .downcast_ref::<Parts<MessageType>>()
+            .expect("the channel has the message type of its key");
+        let channel = UseChannel::from_parts(
+            named.id,
+            *overflow,
+            sender.clone(),
+            inactive_receiver.clone(),
+            forward.clone(),
+        );
+        drop(channels);
+
+        (registry, key, channel)
+    });
+
+    use_drop(move || {
+        let mut channels = registry.0.borrow_mut();
+        if let Some(named) = channels.get_mut(&key) {
+            named.users -= 1;
+            if named.users == 0 {
+                channels.remove(&key);
+            }
+        }
+    });
+
+    channel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        static MOUNTED: Cell<bool> = const { Cell::new(true) };
+        static CHANNELS: RefCell<Vec<UseChannel<u32>>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[component]
+    fn User(name: &'static str, size: usize) -> Element {
+        let channel = use_named_channel_with_config::<u32>(name, ChannelConfig::new(size));
+        use_hook(|| CHANNELS.with(|channels| channels.borrow_mut().push(channel)));
+        rsx! {}
+    }
+
+    fn app() -> Element {
+        if !MOUNTED.get() {
+            return rsx! {};
+        }
+        rsx! {
+            User { name: "a", size: 4 }
+            User { name: "a", size: 8 }
+            User { name: "b", size: 8 }
+        }
+    }
+
+    fn rerender(dom: &mut VirtualDom, mounted: bool) -> Vec<UseChannel<u32>> {
+        MOUNTED.set(mounted);
+        dom.mark_dirty(ScopeId::ROOT);
+        dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        CHANNELS.take()
+    }
+
+    #[test]
+    fn shares_channels_by_name() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        let [a, other_a, b] = <[_; 3]>::try_from(CHANNELS.take()).ok().unwrap();
+
+        dom.in_runtime(|| {
+            assert!(a == other_a && a != b);
+            // The first component to use the name configures the channel
+            assert_eq!(other_a.capacity(), 4);
+
+            let (mut other_a, mut b) = (other_a, b);
+            let (mut a_receiver, mut b_receiver) = (other_a.receiver(), b.receiver());
+            a.try_send(1u32).unwrap();
+            assert_eq!(a_receiver.try_recv(), Ok(1));
+            assert!(b_receiver.try_recv().is_err());
+        });
+
+        // The channels are removed when all their users unmount, so the names can be used again
+        assert!(rerender(&mut dom, false).is_empty());
+        let channels = rerender(&mut dom, true);
+        assert_eq!(channels.len(), 3);
+        assert!(channels[0] == channels[1] && channels[0] != a);
+    }
+}
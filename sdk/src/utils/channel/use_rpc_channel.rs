@@ -0,0 +1,367 @@
+use dioxus::prelude::*;
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{self, Either},
+    Future, StreamExt,
+};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    rc::Rc,
+    time::Duration,
+};
+use uuid::Uuid;
+
+/// Represents errors when calling an RPC channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcError {
+    /// No component with a [`use_rpc_handler`] for the channel is mounted.
+    NoHandler,
+    /// The handler didn't respond within the timeout of the channel, see [`UseRpcChannel::with_timeout`].
+    Timeout,
+    /// The component of the handler was unmounted before it responded.
+    HandlerDropped,
+}
+
+impl std::error::Error for RpcError {}
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RpcError::NoHandler => write!(f, "no handler is mounted for the channel"),
+            RpcError::Timeout => write!(f, "the handler didn't respond in time"),
+            RpcError::HandlerDropped => write!(f, "the handler was unmounted before responding"),
+        }
+    }
+}
+
+/// A request along with where to send its response.
+type Call<Request, Response> = (Request, oneshot::Sender<Response>);
+
+type RpcKey = (String, TypeId);
+
+/// The handlers of the RPC channels of the app, provided at the root.
+///
+/// Channels are found by their name and request and response types.
+/// Calls aren't broadcast over a [`UseChannel`](super::UseChannel), as each call must reach exactly one handler along with where to send its response.
+#[derive(Clone, Default)]
+struct RpcRegistry(Rc<RefCell<HashMap<RpcKey, Vec<RegisteredHandler>>>>);
+
+struct RegisteredHandler {
+    id: Uuid,
+    /// Where the calls to the handler are sent.
+    calls: Box<dyn Any>,
+}
+
+impl RpcRegistry {
+    fn current() -> Self {
+        try_consume_context::<RpcRegistry>()
+            .unwrap_or_else(|| provide_root_context(RpcRegistry::default()))
+    }
+
+    fn key<Request: 'static, Response: 'static>(name: &str) -> RpcKey {
+        (name.to_string(), TypeId::of::<Call<Request, Response>>())
+    }
+
+    /// Adds a handler, which receives the calls until it's unregistered or another handler is registered.
+    fn register<Request: 'static, Response: 'static>(
+        &self,
+        name: &str,
+        id: Uuid,
+        calls: mpsc::UnboundedSender<Call<Request, Response>>,
+    ) {
+        self.0
+            .borrow_mut()
+            .entry(Self::key::<Request, Response>(name))
+            .or_default()
+            .push(RegisteredHandler {
+                id,
+                calls: Box::new(calls),
+            });
+    }
+
+    fn unregister(&self, key: &RpcKey, id: Uuid) {
+        let mut handlers = self.0.borrow_mut();
+        if let Some(registered) = handlers.get_mut(key) {
+            registered.retain(|handler| handler.id != id);
+            if registered.is_empty() {
+                handlers.remove(key);
+            }
+        }
+    }
+
+    /// Where to send the calls of the channel, to the handler that was registered last.
+    fn calls<Request: 'static, Response: 'static>(
+        &self,
+        name: &str,
+    ) -> Option<mpsc::UnboundedSender<Call<Request, Response>>> {
+        let handlers = self.0.borrow();
+        let handler = handlers
+            .get(&Self::key::<Request, Response>(name))?
+            .last()?;
+        handler
+            .calls
+            .downcast_ref::<mpsc::UnboundedSender<Call<Request, Response>>>()
+            .cloned()
+    }
+}
+
+/// Call the handler of an RPC channel and wait for its response.
+pub struct UseRpcChannel<Request: 'static, Response: 'static> {
+    name: Signal<String>,
+    registry: Signal<RpcRegistry>,
+    timeout: Option<Duration>,
+    _types: PhantomData<fn(Request) -> Response>,
+}
+
+impl<Request, Response> Clone for UseRpcChannel<Request, Response> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Request, Response> Copy for UseRpcChannel<Request, Response> {}
+
+impl<Request, Response> PartialEq for UseRpcChannel<Request, Response> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.timeout == other.timeout
+    }
+}
+
+impl<Request: 'static, Response: 'static> UseRpcChannel<Request, Response> {
+    /// Fails calls with [`RpcError::Timeout`] when the handler doesn't respond within `timeout`.
+    ///
+    /// Without a timeout, calls wait for as long as the handler takes.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Whether a component with a [`use_rpc_handler`] for the channel is mounted.
+    pub fn has_handler(&self) -> bool {
+        self.calls().is_some()
+    }
+
+    /// Sends a request to the handler of the channel and waits for its response.
+    pub async fn call(&self, request: impl Into<Request>) -> Result<Response, RpcError> {
+        let calls = self.calls().ok_or(RpcError::NoHandler)?;
+        let (reply, response) = oneshot::channel();
+        calls
+            .unbounded_send((request.into(), reply))
+            .map_err(|_| RpcError::NoHandler)?;
+
+        let Some(timeout) = self.timeout else {
+            return response.await.map_err(|_| RpcError::HandlerDropped);
+        };
+        match future::select(response, Box::pin(sleep(timeout))).await {
+            Either::Left((response, _)) => response.map_err(|_| RpcError::HandlerDropped),
+            Either::Right(_) => Err(RpcError::Timeout),
+        }
+    }
+
+    fn calls(&self) -> Option<mpsc::UnboundedSender<Call<Request, Response>>> {
+        self.registry.peek().calls(&self.name.peek())
+    }
+}
+
+async fn sleep(duration: Duration) {
+    #[cfg(target_family = "wasm")]
+    gloo_timers::future::sleep(duration).await;
+
+    #[cfg(not(target_family = "wasm"))]
+    tokio::time::sleep(duration).await;
+}
+
+/// Call a handler in another component by the name of its channel, and wait for its response.
+///
+/// The handler is registered with [`use_rpc_handler`] using the same name, request and response types.
+/// When multiple handlers are mounted the one that was mounted last responds, and calls fail with [`RpcError::NoHandler`] while none is.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::utils::channel::{use_rpc_channel, use_rpc_handler};
+/// use std::time::Duration;
+///
+/// fn App() -> Element {
+///     rsx! {
+///         Dialog {}
+///         DeleteButton {}
+///     }
+/// }
+///
+/// #[component]
+/// fn Dialog() -> Element {
+///     let mut question = use_signal(|| None::<String>);
+///     use_rpc_handler("confirm", move |text: String| async move {
+///         question.set(Some(text));
+///         // Ask the user...
+///         true
+///     });
+///     rsx! {
+///         if let Some(question) = question() {
+///             p { "{question}" }
+///         }
+///     }
+/// }
+///
+/// #[component]
+/// fn DeleteButton() -> Element {
+///     let confirm = use_rpc_channel::<String, bool>("confirm").with_timeout(Duration::from_secs(30));
+///     rsx! {
+///         button {
+///             onclick: move |_| async move {
+///                 if confirm.call("Delete the file?").await == Ok(true) {
+///                     // Delete the file...
+///                 }
+///             },
+///             "Delete"
+///         }
+///     }
+/// }
+/// ```
+pub fn use_rpc_channel<Request: 'static, Response: 'static>(
+    name: &str,
+) -> UseRpcChannel<Request, Response> {
+    use_hook(|| UseRpcChannel {
+        name: Signal::new(name.to_string()),
+        registry: Signal::new(RpcRegistry::current()),
+        timeout: None,
+        _types: PhantomData,
+    })
+}
+
+/// Respond to the calls of the RPC channel with the given name, see [`use_rpc_channel`].
+///
+/// Every call is handled in its own task of this component, so a slow response doesn't hold up the other calls.
+/// The handler stops receiving calls when this component is unmounted, and the calls it didn't respond to yet fail with [`RpcError::HandlerDropped`].
+pub fn use_rpc_handler<Request: 'static, Response: 'static, Handler>(
+    name: &str,
+    handler: impl Fn(Request) -> Handler + 'static,
+) where
+    Handler: Future<Output = Response> + 'static,
+{
+    let (registry, key, id) = use_hook(|| {
+        let registry = RpcRegistry::current();
+        let id = Uuid::new_v4();
+        let (calls, mut received) = mpsc::unbounded::<Call<Request, Response>>();
+        registry.register(name, id, calls);
+
+        spawn(async move {
+            while let Some((request, reply)) = received.next().await {
+                let response = handler(request);
+                spawn(async move {
+                    // The caller might have stopped waiting for the response
+                    reply.send(response.await).ok();
+                });
+            }
+        });
+
+        (registry, RpcRegistry::key::<Request, Response>(name), id)
+    });
+
+    use_drop(move || registry.unregister(&key, id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus::dioxus_core::NoOpMutations;
+    use std::{cell::Cell, pin::Pin, task::Poll};
+
+    type Double = UseRpcChannel<u32, u32>;
+
+    thread_local! {
+        static APP: Cell<Option<(Double, Signal<bool>)>> = const { Cell::new(None) };
+    }
+
+    fn app() -> Element {
+        let mounted = use_signal(|| true);
+        let double = use_rpc_channel::<u32, u32>("double");
+        use_hook(|| APP.set(Some((double, mounted))));
+        rsx! {
+            if mounted() {
+                Handler {}
+            }
+        }
+    }
+
+    #[component]
+    fn Handler() -> Element {
+        use_rpc_handler("double", |n: u32| async move {
+            // Never respond to zero
+            if n == 0 {
+                future::pending::<()>().await;
+            }
+            n * 2
+        });
+        rsx! {}
+    }
+
+    /// Runs the tasks of the app until the call is done, or gives up after a few rounds.
+    async fn run<T>(
+        dom: &mut VirtualDom,
+        call: &mut Pin<Box<impl Future<Output = T>>>,
+    ) -> Option<T> {
+        for _ in 0..10 {
+            if let Poll::Ready(response) = futures::poll!(call.as_mut()) {
+                return Some(response);
+            }
+            dom.process_events();
+        }
+        None
+    }
+
+    #[tokio::test]
+    async fn calls_the_mounted_handler() {
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        let (double, mut mounted) = APP.get().unwrap();
+        assert!(double.has_handler());
+
+        let mut call = Box::pin(double.call(21u32));
+        assert_eq!(run(&mut dom, &mut call).await, Some(Ok(42)));
+
+        // The handler doesn't respond in time
+        let timed = double.with_timeout(Duration::from_millis(10));
+        let mut call = Box::pin(timed.call(0u32));
+        assert_eq!(run(&mut dom, &mut call).await, None);
+        assert_eq!(call.await, Err(RpcError::Timeout));
+
+        // The handler is unmounted before it responds
+        let mut call = Box::pin(double.call(0u32));
+        assert_eq!(run(&mut dom, &mut call).await, None);
+        dom.in_runtime(|| ScopeId::ROOT.in_runtime(|| mounted.set(false)));
+        dom.render_immediate(&mut NoOpMutations);
+        assert_eq!(
+            run(&mut dom, &mut call).await,
+            Some(Err(RpcError::HandlerDropped))
+        );
+
+        assert!(!double.has_handler());
+        assert_eq!(double.call(1u32).await, Err(RpcError::NoHandler));
+    }
+
+    #[test]
+    fn calls_the_last_registered_handler() {
+        let registry = RpcRegistry::default();
+        assert!(registry.calls::<u32, u32>("double").is_none());
+
+        let (first, _first_calls) = mpsc::unbounded::<Call<u32, u32>>();
+        let (second, _second_calls) = mpsc::unbounded::<Call<u32, u32>>();
+        let (first_id, second_id) = (Uuid::new_v4(), Uuid::new_v4());
+        registry.register("double", first_id, first.clone());
+        registry.register("double", second_id, second.clone());
+        assert!(registry.calls::<u32, String>("double").is_none());
+
+        let key = RpcRegistry::key::<u32, u32>("double");
+        let calls = registry.calls::<u32, u32>("double").unwrap();
+        assert!(calls.same_receiver(&second));
+        registry.unregister(&key, second_id);
+        let calls = registry.calls::<u32, u32>("double").unwrap();
+        assert!(calls.same_receiver(&first));
+        registry.unregister(&key, first_id);
+        assert!(registry.0.borrow().is_empty());
+    }
+}
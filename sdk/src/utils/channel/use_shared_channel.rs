@@ -0,0 +1,256 @@
+use async_broadcast::{Sender, TrySendError};
+use serde::{de::DeserializeOwned, Serialize};
+use std::rc::Rc;
+
+use super::use_channel::Forward;
+use super::use_named_channel::use_registered_channel;
+use super::{ChannelConfig, UseChannel, NAMED_CHANNEL_SIZE};
+
+/// Send and listen for messages between components in any window of the app or, on web, in any tab of the site.
+///
+/// This is a [named channel](super::use_named_channel()) whose messages are also sent to the channels with the same name in other windows or tabs,
+/// so they are received with [`use_listen_channel`](super::use_listen_channel()) there.
+/// Messages are serialized to JSON to be sent, and other windows or tabs only receive messages sent after their channel was created.
+///
+/// On web this uses a [`BroadcastChannel`](https://developer.mozilla.org/en-US/docs/Web/API/BroadcastChannel), on desktop the windows of the app share the messages.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_sdk::utils::channel::{use_listen_channel, use_shared_channel};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct LoggedOut;
+///
+/// fn App() -> Element {
+///     let mut logged_in = use_signal(|| true);
+///     let channel = use_shared_channel::<LoggedOut>("session");
+///     // Log out in every tab when the user logs out in one of them
+///     use_listen_channel(&channel, move |message| async move {
+///         if message.is_ok() {
+///             logged_in.set(false);
+///         }
+///     });
+///
+///     rsx! {
+///         button {
+///             onclick: move |_| {
+///                 logged_in.set(false);
+///                 channel.try_send(LoggedOut).ok();
+///             },
+///             "Log out"
+///         }
+///     }
+/// }
+/// ```
+pub fn use_shared_channel<MessageType>(name: &str) -> UseChannel<MessageType>
+where
+    MessageType: Clone + Serialize + DeserializeOwned + 'static,
+{
+    use_shared_channel_with_config(name, ChannelConfig::new(NAMED_CHANNEL_SIZE))
+}
+
+/// Like [`use_shared_channel`], with a configured channel, see [`use_named_channel_with_config`](super::use_named_channel_with_config()).
+pub fn use_shared_channel_with_config<MessageType>(
+    name: &str,
+    config: ChannelConfig,
+) -> UseChannel<MessageType>
+where
+    MessageType: Clone + Serialize + DeserializeOwned + 'static,
+{
+    use_registered_channel(name, config, Some(connect::<MessageType>))
+}
+
+/// Receives the messages from other windows or tabs and returns how to send messages to them.
+fn connect<MessageType>(name: &str, sender: Sender<MessageType>) -> Forward<MessageType>
+where
+    MessageType: Clone + Serialize + DeserializeOwned + 'static,
+{
+    let channel_name = name.to_string();
+    let transport = Transport::subscribe::<MessageType>(name, move |message| {
+        match serde_json::from_str::<MessageType>(message) {
+            // The message is put in this channel directly, so it isn't sent back to the other windows or tabs
+            Ok(message) => match sender.try_broadcast(message) {
+                Ok(_) | Err(TrySendError::Inactive(_)) => {}
+                Err(err) => {
+                    tracing::warn!("Dropped a message of channel \"{}\": {}", channel_name, err)
+                }
+            },
+            Err(err) => tracing::error!(
+                "Failed to deserialize a message of channel \"{}\": {}",
+                channel_name,
+                err
+            ),
+        }
+    });
+
+    let channel_name = name.to_string();
+    Rc::new(
+        move |message: &MessageType| match serde_json::to_string(message) {
+            Ok(message) => transport.post(&message),
+            Err(err) => tracing::error!(
+                "Failed to serialize a message of channel \"{}\": {}",
+                channel_name,
+                err
+            ),
+        },
+    )
+}
+
+// Web implementation of the transport between tabs.
+#[cfg(target_family = "wasm")]
+struct Transport {
+    channel: Option<web_sys::BroadcastChannel>,
+    _on_message: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::MessageEvent)>,
+}
+
+#[cfg(target_family = "wasm")]
+impl Transport {
+    /// Subscribes to the messages of the channel, which are told apart by its name alone between tabs.
+    fn subscribe<MessageType: 'static>(name: &str, on_message: impl Fn(&str) + 'static) -> Self {
+        use wasm_bindgen::{closure::Closure, JsCast};
+
+        let on_message = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+            if let Some(message) = event.data().as_string() {
+                on_message(&message);
+            }
+        }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+
+        let channel = web_sys::BroadcastChannel::new(&format!("dioxus-sdk-channel:{name}"));
+        let channel = match channel {
+            Ok(channel) => {
+                channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+                Some(channel)
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Failed to create a broadcast channel for \"{}\": {:?}",
+                    name,
+                    err
+                );
+                None
+            }
+        };
+        Self {
+            channel,
+            _on_message: on_message,
+        }
+    }
+
+    fn post(&self, message: &str) {
+        if let Some(channel) = &self.channel {
+            channel
+                .post_message(&wasm_bindgen::JsValue::from_str(message))
+                .ok();
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+impl Drop for Transport {
+    fn drop(&mut self) {
+        if let Some(channel) = &self.channel {
+            channel.set_onmessage(None);
+            channel.close();
+        }
+    }
+}
+
+/// The windows that receive the messages of each channel, with the transports they subscribed with.
+///
+/// Like named channels, they are found by their name and message type, so channels that only share a name don't get each other's messages.
+#[cfg(not(target_family = "wasm"))]
+type Subscribers =
+    std::collections::HashMap<(String, std::any::TypeId), Vec<(uuid::Uuid, Rc<dyn Fn(&str)>)>>;
+
+// Desktop implementation of the transport between windows, which all run on the main thread.
+#[cfg(not(target_family = "wasm"))]
+thread_local! {
+    static SUBSCRIBERS: std::cell::RefCell<Subscribers> = std::cell::RefCell::default();
+}
+
+#[cfg(not(target_family = "wasm"))]
+struct Transport {
+    key: (String, std::any::TypeId),
+    id: uuid::Uuid,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl Transport {
+    fn subscribe<MessageType: 'static>(name: &str, on_message: impl Fn(&str) + 'static) -> Self {
+        let key = (name.to_string(), std::any::TypeId::of::<MessageType>());
+        let id = uuid::Uuid::new_v4();
+        SUBSCRIBERS.with(|subscribers| {
+            subscribers
+                .borrow_mut()
+                .entry(key.clone())
+                .or_default()
+                .push((id, Rc::new(on_message)));
+        });
+        Self { key, id }
+    }
+
+    fn post(&self, message: &str) {
+        // Collect the other windows first, so they can send messages while receiving this one
+        let others = SUBSCRIBERS.with(|subscribers| {
+            subscribers
+                .borrow()
+                .get(&self.key)
+                .into_iter()
+                .flatten()
+                .filter(|(id, _)| *id != self.id)
+                .map(|(_, on_message)| on_message.clone())
+                .collect::<Vec<_>>()
+        });
+        for on_message in others {
+            on_message(message);
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl Drop for Transport {
+    fn drop(&mut self) {
+        SUBSCRIBERS.with(|subscribers| {
+            let mut subscribers = subscribers.borrow_mut();
+            if let Some(others) = subscribers.get_mut(&self.key) {
+                others.retain(|(id, _)| *id != self.id);
+                if others.is_empty() {
+                    subscribers.remove(&self.key);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn posts_to_other_windows() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let subscribe = |window: &'static str| {
+            let received = received.clone();
+            Transport::subscribe::<String>("test", move |message| {
+                received.borrow_mut().push(format!("{window}: {message}"))
+            })
+        };
+        let first = subscribe("first");
+        let second = subscribe("second");
+        // A channel with the same name but another message type
+        let other_type = {
+            let received = received.clone();
+            Transport::subscribe::<u32>("test", move |message| {
+                received.borrow_mut().push(format!("other type: {message}"))
+            })
+        };
+
+        first.post("hello");
+        drop(second);
+        first.post("bye");
+        other_type.post("1");
+        assert_eq!(*received.borrow(), ["second: hello"]);
+    }
+}